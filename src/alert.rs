@@ -0,0 +1,116 @@
+use std::collections::HashSet;
+
+use bleah::DeviceInfo;
+
+pub struct Alert {
+    pub id: String,
+    pub message: String,
+}
+
+// Watches the device list for targets (address or name substrings). Appearing alerts once
+// per device; with a threshold, coming within range alerts again after each drop below it.
+#[derive(Default)]
+pub struct AlertWatcher {
+    targets: Vec<String>,
+    rssi_threshold: Option<i16>,
+    seen: HashSet<String>,
+    in_range: HashSet<String>,
+}
+
+impl AlertWatcher {
+    pub fn new(targets: Vec<String>, rssi_threshold: Option<i16>) -> Self {
+        Self {
+            targets: targets.iter().map(|target| target.to_lowercase()).collect(),
+            rssi_threshold,
+            ..Self::default()
+        }
+    }
+
+    fn matches(&self, device: &DeviceInfo) -> bool {
+        let address = device.address().to_lowercase();
+        let name = device.name.to_lowercase();
+        self.targets
+            .iter()
+            .any(|target| address.contains(target) || name.contains(target))
+    }
+
+    pub fn check(&mut self, devices: &[DeviceInfo]) -> Vec<Alert> {
+        let mut alerts = Vec::new();
+        for device in devices {
+            if !self.matches(device) {
+                continue;
+            }
+            let alert = |message: String| Alert {
+                id: device.id.clone(),
+                message: format!("Alert: {} ({}) {message}", device.name, device.address()),
+            };
+            if self.seen.insert(device.id.clone()) {
+                alerts.push(alert("appeared".to_string()));
+            }
+            let Some(threshold) = self.rssi_threshold else {
+                continue;
+            };
+            match device.rssi_smoothed {
+                Some(rssi) if rssi >= threshold => {
+                    if self.in_range.insert(device.id.clone()) {
+                        alerts.push(alert(format!("is within range ({rssi} dBm)")));
+                    }
+                }
+                _ => {
+                    self.in_range.remove(&device.id);
+                }
+            }
+        }
+        alerts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::time::UNIX_EPOCH;
+
+    use super::*;
+
+    fn device(rssi: i16) -> DeviceInfo {
+        DeviceInfo {
+            id: "hci0/dev_AA_BB_CC_DD_EE_FF".to_string(),
+            address: "AA:BB:CC:DD:EE:FF".to_string(),
+            name: "Tag".to_string(),
+            rssi: Some(rssi),
+            rssi_smoothed: Some(rssi),
+            connected: false,
+            tx_power_level: None,
+            address_type: None,
+            appearance: None,
+            connectable: None,
+            raw_adv: None,
+            manufacturer_data: BTreeMap::new(),
+            service_data: BTreeMap::new(),
+            services: Vec::new(),
+            adv_count: 1,
+            first_seen: UNIX_EPOCH,
+            last_seen: UNIX_EPOCH,
+        }
+    }
+
+    #[test]
+    fn matches_colon_formatted_address() {
+        let mut watcher = AlertWatcher::new(vec!["aa:bb:cc:dd:ee:ff".to_string()], None);
+        let alerts = watcher.check(&[device(-70)]);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].message, "Alert: Tag (AA:BB:CC:DD:EE:FF) appeared");
+        // Appearing alerts only once.
+        assert!(watcher.check(&[device(-70)]).is_empty());
+    }
+
+    #[test]
+    fn alerts_again_on_coming_back_into_range() {
+        let mut watcher = AlertWatcher::new(vec!["tag".to_string()], Some(-60));
+        assert_eq!(watcher.check(&[device(-70)]).len(), 1);
+        assert_eq!(watcher.check(&[device(-50)]).len(), 1);
+        assert!(watcher.check(&[device(-50)]).is_empty());
+        assert!(watcher.check(&[device(-70)]).is_empty());
+        assert_eq!(watcher.check(&[device(-55)]).len(), 1);
+    }
+}
@@ -0,0 +1,202 @@
+// Subset of the Bluetooth SIG "Company Identifiers" assigned numbers, sorted by id so
+// lookups can binary search.
+pub(crate) static COMPANY_IDS: &[(u16, &str)] = &[
+    (0x0000, "Ericsson"),
+    (0x0001, "Nokia"),
+    (0x0002, "Intel"),
+    (0x0003, "IBM"),
+    (0x0004, "Toshiba"),
+    (0x0005, "3Com"),
+    (0x0006, "Microsoft"),
+    (0x0007, "Lucent"),
+    (0x0008, "Motorola"),
+    (0x0009, "Infineon Technologies"),
+    (0x000a, "Qualcomm Technologies International"),
+    (0x000d, "Texas Instruments"),
+    (0x000f, "Broadcom"),
+    (0x001d, "Qualcomm"),
+    (0x0030, "STMicroelectronics"),
+    (0x0046, "MediaTek"),
+    (0x004c, "Apple"),
+    (0x0059, "Nordic Semiconductor"),
+    (0x005d, "Realtek Semiconductor"),
+    (0x0065, "HP"),
+    (0x0067, "GN Netcom"),
+    (0x006b, "Polar Electro"),
+    (0x0075, "Samsung Electronics"),
+    (0x0078, "Nike"),
+    (0x0087, "Garmin International"),
+    (0x009e, "Bose"),
+    (0x00c4, "LG Electronics"),
+    (0x00d2, "Dialog Semiconductor"),
+    (0x00dc, "Procter & Gamble"),
+    (0x00e0, "Google"),
+    (0x0118, "Radius Networks"),
+    (0x012d, "Sony"),
+    (0x0131, "Cypress Semiconductor"),
+    (0x0157, "Anhui Huami Information Technology"),
+    (0x0171, "Amazon"),
+    (0x018e, "Google"),
+    (0x01da, "Logitech"),
+    (0x02e1, "Victron Energy"),
+    (0x02e5, "Espressif"),
+    (0x038f, "Xiaomi"),
+    (0x0499, "Ruuvi Innovations"),
+    (0x05a7, "Sonos"),
+    (0x07d0, "Tuya"),
+    (0x0822, "Adafruit Industries"),
+    (0x0969, "Woan Technology (SwitchBot)"),
+];
+
+// 16-bit service UUIDs from the GATT assigned numbers, plus a few well-known member
+// services, sorted by id.
+pub(crate) static SERVICE_UUIDS: &[(u16, &str)] = &[
+    (0x1800, "Generic Access"),
+    (0x1801, "Generic Attribute"),
+    (0x1802, "Immediate Alert"),
+    (0x1803, "Link Loss"),
+    (0x1804, "Tx Power"),
+    (0x1805, "Current Time"),
+    (0x1806, "Reference Time Update"),
+    (0x1807, "Next DST Change"),
+    (0x1808, "Glucose"),
+    (0x1809, "Health Thermometer"),
+    (0x180a, "Device Information"),
+    (0x180d, "Heart Rate"),
+    (0x180e, "Phone Alert Status"),
+    (0x180f, "Battery"),
+    (0x1810, "Blood Pressure"),
+    (0x1811, "Alert Notification"),
+    (0x1812, "Human Interface Device"),
+    (0x1813, "Scan Parameters"),
+    (0x1814, "Running Speed and Cadence"),
+    (0x1815, "Automation IO"),
+    (0x1816, "Cycling Speed and Cadence"),
+    (0x1818, "Cycling Power"),
+    (0x1819, "Location and Navigation"),
+    (0x181a, "Environmental Sensing"),
+    (0x181b, "Body Composition"),
+    (0x181c, "User Data"),
+    (0x181d, "Weight Scale"),
+    (0x181e, "Bond Management"),
+    (0x181f, "Continuous Glucose Monitoring"),
+    (0x1820, "Internet Protocol Support"),
+    (0x1821, "Indoor Positioning"),
+    (0x1822, "Pulse Oximeter"),
+    (0x1823, "HTTP Proxy"),
+    (0x1824, "Transport Discovery"),
+    (0x1825, "Object Transfer"),
+    (0x1826, "Fitness Machine"),
+    (0x1827, "Mesh Provisioning"),
+    (0x1828, "Mesh Proxy"),
+    (0x1829, "Reconnection Configuration"),
+    (0x183a, "Insulin Delivery"),
+    (0x183b, "Binary Sensor"),
+    (0x183c, "Emergency Configuration"),
+    (0x183e, "Physical Activity Monitor"),
+    (0x1843, "Audio Input Control"),
+    (0x1844, "Volume Control"),
+    (0x1845, "Volume Offset Control"),
+    (0x1846, "Coordinated Set Identification"),
+    (0x1848, "Media Control"),
+    (0x1849, "Generic Media Control"),
+    (0x184e, "Audio Stream Control"),
+    (0x184f, "Broadcast Audio Scan"),
+    (0x1850, "Published Audio Capabilities"),
+    (0x1851, "Basic Audio Announcement"),
+    (0x1852, "Broadcast Audio Announcement"),
+    (0xfcd2, "BTHome"),
+    (0xfd6f, "Exposure Notification"),
+    (0xfe2c, "Google Fast Pair"),
+    (0xfe59, "Nordic Secure DFU"),
+    (0xfe95, "Xiaomi"),
+    (0xfeaa, "Eddystone"),
+    (0xfeed, "Tile"),
+];
+
+// GAP Appearance values: a 10-bit category in the high bits and a 6-bit subcategory, sorted
+// by value.
+pub(crate) static APPEARANCES: &[(u16, &str)] = &[
+    (0x0000, "Unknown"),
+    (0x0040, "Phone"),
+    (0x0080, "Computer"),
+    (0x00c0, "Watch"),
+    (0x00c1, "Sports Watch"),
+    (0x00c2, "Smartwatch"),
+    (0x0100, "Clock"),
+    (0x0140, "Display"),
+    (0x0180, "Remote Control"),
+    (0x01c0, "Eye-glasses"),
+    (0x0200, "Tag"),
+    (0x0240, "Keyring"),
+    (0x0280, "Media Player"),
+    (0x02c0, "Barcode Scanner"),
+    (0x0300, "Thermometer"),
+    (0x0301, "Ear Thermometer"),
+    (0x0340, "Heart Rate Sensor"),
+    (0x0341, "Heart Rate Belt"),
+    (0x0380, "Blood Pressure"),
+    (0x0381, "Arm Blood Pressure"),
+    (0x0382, "Wrist Blood Pressure"),
+    (0x03c0, "Human Interface Device"),
+    (0x03c1, "Keyboard"),
+    (0x03c2, "Mouse"),
+    (0x03c3, "Joystick"),
+    (0x03c4, "Gamepad"),
+    (0x03c5, "Digitizer Tablet"),
+    (0x03c6, "Card Reader"),
+    (0x03c7, "Digital Pen"),
+    (0x03c8, "Barcode Scanner"),
+    (0x03c9, "Touchpad"),
+    (0x03ca, "Presentation Remote"),
+    (0x0400, "Glucose Meter"),
+    (0x0440, "Running Walking Sensor"),
+    (0x0480, "Cycling"),
+    (0x0481, "Cycling Computer"),
+    (0x0482, "Speed Sensor"),
+    (0x0483, "Cadence Sensor"),
+    (0x0484, "Power Sensor"),
+    (0x0485, "Speed and Cadence Sensor"),
+    (0x04c0, "Control Device"),
+    (0x0500, "Network Device"),
+    (0x0540, "Sensor"),
+    (0x0580, "Light Fixtures"),
+    (0x05c0, "Fan"),
+    (0x0600, "HVAC"),
+    (0x0640, "Air Conditioning"),
+    (0x0680, "Humidifier"),
+    (0x06c0, "Heating"),
+    (0x0700, "Access Control"),
+    (0x0740, "Motorized Device"),
+    (0x0780, "Power Device"),
+    (0x07c0, "Light Source"),
+    (0x0800, "Window Covering"),
+    (0x0840, "Audio Sink"),
+    (0x0841, "Standalone Speaker"),
+    (0x0842, "Soundbar"),
+    (0x0843, "Bookshelf Speaker"),
+    (0x0880, "Audio Source"),
+    (0x08c0, "Motorized Vehicle"),
+    (0x0900, "Domestic Appliance"),
+    (0x0940, "Wearable Audio Device"),
+    (0x0941, "Earbud"),
+    (0x0942, "Headset"),
+    (0x0943, "Headphones"),
+    (0x0944, "Neck Band"),
+    (0x0980, "Aircraft"),
+    (0x09c0, "AV Equipment"),
+    (0x0a00, "Display Equipment"),
+    (0x0a40, "Hearing Aid"),
+    (0x0a80, "Gaming"),
+    (0x0ac0, "Signage"),
+    (0x0c40, "Pulse Oximeter"),
+    (0x0c41, "Fingertip Pulse Oximeter"),
+    (0x0c42, "Wrist Worn Pulse Oximeter"),
+    (0x0c80, "Weight Scale"),
+    (0x0cc0, "Personal Mobility Device"),
+    (0x0d00, "Continuous Glucose Monitor"),
+    (0x0d40, "Insulin Pump"),
+    (0x0d80, "Medication Delivery"),
+    (0x0dc0, "Spirometer"),
+    (0x1440, "Outdoor Sports Activity"),
+];
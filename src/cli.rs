@@ -0,0 +1,182 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use bleah::replay::ReplayConfig;
+use bleah::{AdapterSelector, ScanConfig};
+use clap::{ArgGroup, Parser};
+use uuid::Uuid;
+
+use crate::config::Config;
+
+#[derive(Debug, Parser)]
+#[command(name = "bleah", version, about = "Scan and decode nearby BLE devices")]
+#[command(group(ArgGroup::new("output").multiple(true)))]
+pub struct Cli {
+    /// Print the available adapters and exit
+    #[arg(long, exclusive = true)]
+    pub list_adapters: bool,
+
+    /// Adapter to scan with, as a zero-based index or a name substring
+    #[arg(long)]
+    pub adapter: Option<String>,
+
+    /// Drop devices whose RSSI is below this value (dBm)
+    #[arg(long, allow_negative_numbers = true)]
+    pub min_rssi: Option<i16>,
+
+    /// How often the device list is refreshed (e.g. 500ms, 2s) [default: 2s]
+    #[arg(long, value_parser = humantime::parse_duration)]
+    pub poll_interval: Option<Duration>,
+
+    /// Forget devices not heard from for this long (e.g. 10m, 1h) [default: 10m]
+    #[arg(long, value_parser = humantime::parse_duration)]
+    pub forget_after: Option<Duration>,
+
+    /// Only keep devices whose name or address contains this text
+    #[arg(long)]
+    pub filter_name: Option<String>,
+
+    /// Only scan for devices advertising this service (repeatable; e.g. 181a or a full UUID)
+    #[arg(long = "service", value_name = "UUID", value_parser = bleah::parse_service_uuid)]
+    pub services: Vec<Uuid>,
+
+    /// Turn off a decoder by name (repeatable; e.g. "Ruuvi" or "Apple Continuity")
+    #[arg(long = "disable-decoder", value_name = "NAME")]
+    pub disable_decoders: Vec<String>,
+
+    /// Alert when a device whose address or name contains this text appears (repeatable)
+    #[arg(long = "alert", value_name = "TEXT")]
+    pub alerts: Vec<String>,
+
+    /// Also alert when an alert target's RSSI rises to this value (dBm)
+    #[arg(long, allow_negative_numbers = true)]
+    pub alert_rssi: Option<i16>,
+
+    /// Start with unnamed devices hidden
+    #[arg(long)]
+    pub hide_unknown: bool,
+
+    /// Stop scanning and exit after this long (e.g. 30s, 5m)
+    #[arg(long, value_parser = humantime::parse_duration)]
+    pub duration: Option<Duration>,
+
+    /// Replay a recorded --json or --ndjson file instead of scanning
+    #[arg(long, value_name = "PATH")]
+    pub replay: Option<PathBuf>,
+
+    /// Fixed delay between replayed snapshots (e.g. 500ms) [default: as recorded]
+    #[arg(long, requires = "replay", value_parser = humantime::parse_duration)]
+    pub replay_interval: Option<Duration>,
+
+    /// Start the replay over once it reaches the end
+    #[arg(long = "loop", requires = "replay")]
+    pub replay_loop: bool,
+
+    /// Run without the TUI; requires an output such as --ndjson
+    #[arg(long, requires = "output")]
+    pub no_tui: bool,
+
+    /// Stream devices to stdout as newline-delimited JSON
+    #[arg(long, group = "output", requires = "no_tui")]
+    pub ndjson: bool,
+
+    /// Write the final device snapshot to this JSON file on exit
+    #[arg(long, value_name = "PATH", group = "output")]
+    pub json: Option<PathBuf>,
+
+    /// Write the final device snapshot to this CSV file on exit
+    #[arg(long, value_name = "PATH", group = "output")]
+    pub csv: Option<PathBuf>,
+
+    /// Write decoded readings as InfluxDB line protocol to a file or an http:// /write URL
+    #[arg(long, value_name = "PATH|URL", group = "output")]
+    pub influx: Option<String>,
+
+    /// Write recorded raw advertisements to this pcap file for Wireshark (with --replay)
+    #[arg(long, value_name = "PATH", group = "output")]
+    pub pcap: Option<PathBuf>,
+
+    /// Serve Prometheus metrics on this address (e.g. 127.0.0.1:9184)
+    #[cfg(feature = "metrics")]
+    #[arg(long, value_name = "ADDR", group = "output")]
+    pub metrics: Option<std::net::SocketAddr>,
+
+    /// Log every poll's observations to this SQLite database
+    #[cfg(feature = "sqlite")]
+    #[arg(long, value_name = "PATH", group = "output")]
+    pub sqlite: Option<PathBuf>,
+
+    /// Publish decoded readings to an MQTT broker (host[:port])
+    #[cfg(feature = "mqtt")]
+    #[arg(long, group = "output")]
+    pub mqtt: Option<String>,
+
+    /// Base topic for MQTT publishing
+    #[cfg(feature = "mqtt")]
+    #[arg(long, default_value = "bleah")]
+    pub mqtt_topic: String,
+}
+
+impl Cli {
+    pub fn scan_config(&self, config: &Config) -> ScanConfig {
+        let defaults = ScanConfig::default();
+        let adapter = match self.adapter.as_ref().or(config.adapter.as_ref()) {
+            None => AdapterSelector::First,
+            Some(value) => match value.parse() {
+                Ok(index) => AdapterSelector::Index(index),
+                Err(_) => AdapterSelector::Name(value.clone()),
+            },
+        };
+        ScanConfig {
+            adapter,
+            poll_interval: self
+                .poll_interval
+                .or(config.poll_interval)
+                .unwrap_or(defaults.poll_interval),
+            min_rssi: self.min_rssi.or(config.min_rssi),
+            forget_after: self
+                .forget_after
+                .or(config.forget_after)
+                .unwrap_or(defaults.forget_after),
+            name_filter: self.filter_name.clone(),
+            services: if self.services.is_empty() {
+                config.services.clone()
+            } else {
+                self.services.clone()
+            },
+            ..defaults
+        }
+    }
+
+    pub fn replay_config(&self) -> ReplayConfig {
+        ReplayConfig {
+            interval: self.replay_interval,
+            looping: self.replay_loop,
+        }
+    }
+
+    pub fn hide_unknown(&self, config: &Config) -> bool {
+        self.hide_unknown || config.hide_unknown
+    }
+
+    #[cfg(feature = "mqtt")]
+    pub fn mqtt_config(&self) -> anyhow::Result<Option<bleah::mqtt::MqttConfig>> {
+        let Some(broker) = self.mqtt.as_ref() else {
+            return Ok(None);
+        };
+        let mut config = bleah::mqtt::MqttConfig {
+            base_topic: self.mqtt_topic.clone(),
+            ..bleah::mqtt::MqttConfig::default()
+        };
+        match broker.rsplit_once(':') {
+            Some((host, port)) => {
+                config.host = host.to_string();
+                config.port = port
+                    .parse()
+                    .map_err(|_| anyhow::anyhow!("invalid MQTT port {port:?} in {broker:?}"))?;
+            }
+            None => config.host = broker.clone(),
+        }
+        Ok(Some(config))
+    }
+}
@@ -0,0 +1,153 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::{Result, anyhow, bail};
+use bleah::rolling::{LogFormat, RollingLogConfig};
+use serde::{Deserialize, Deserializer};
+use uuid::Uuid;
+
+use crate::theme::ThemeConfig;
+
+const RSSI_METER_FLOOR: i16 = -100;
+const RSSI_METER_CEILING: i16 = -40;
+const LOG_INTERVAL: Duration = Duration::from_secs(10);
+
+// Mirrors the CLI flags; anything given on the command line wins over the file.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub adapter: Option<String>,
+    pub min_rssi: Option<i16>,
+    #[serde(deserialize_with = "deserialize_duration")]
+    pub poll_interval: Option<Duration>,
+    #[serde(deserialize_with = "deserialize_duration")]
+    pub forget_after: Option<Duration>,
+    pub hide_unknown: bool,
+    #[serde(deserialize_with = "deserialize_services")]
+    pub services: Vec<Uuid>,
+    // RSSI range (dBm) the signal meter spans from empty to full.
+    pub rssi_floor: Option<i16>,
+    pub rssi_ceiling: Option<i16>,
+    // Expected RSSI (dBm) at 1 m, for distance estimates of devices that don't advertise
+    // their tx power.
+    pub reference_power: Option<i16>,
+    // Address or name substrings to alert on, as with --alert.
+    pub alerts: Vec<String>,
+    pub alert_rssi: Option<i16>,
+    // Decoder names to leave out, as accepted by --disable-decoder.
+    pub disabled_decoders: Vec<String>,
+    pub theme: ThemeConfig,
+    pub log: LogConfig,
+    // Device address to the hex encryption key shown in the VictronConnect app.
+    pub victron_keys: BTreeMap<String, String>,
+}
+
+impl Config {
+    pub fn rssi_meter(&self) -> (i16, i16) {
+        (
+            self.rssi_floor.unwrap_or(RSSI_METER_FLOOR),
+            self.rssi_ceiling.unwrap_or(RSSI_METER_CEILING),
+        )
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        let (floor, ceiling) = self.rssi_meter();
+        if floor >= ceiling {
+            bail!("rssi_floor ({floor}) must be below rssi_ceiling ({ceiling})");
+        }
+        self.theme.build()?;
+        self.log.build()?;
+        Ok(())
+    }
+}
+
+// Periodic snapshots appended to a rolling file while bleah runs; off unless `path` is set.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LogConfig {
+    pub path: Option<PathBuf>,
+    #[serde(deserialize_with = "deserialize_duration")]
+    pub interval: Option<Duration>,
+    // "json" (one record per line, replayable with --replay) or "csv".
+    pub format: Option<String>,
+    // Roll over to a fresh file past this many bytes or after this long.
+    pub max_bytes: Option<u64>,
+    #[serde(deserialize_with = "deserialize_duration")]
+    pub rotate_every: Option<Duration>,
+}
+
+impl LogConfig {
+    pub fn build(&self) -> Result<Option<RollingLogConfig>> {
+        let Some(path) = &self.path else {
+            return Ok(None);
+        };
+        let format = match &self.format {
+            Some(name) => LogFormat::parse(name)
+                .ok_or_else(|| anyhow!("unknown log format {name:?} (expected json or csv)"))?,
+            None => LogFormat::default(),
+        };
+        let interval = self.interval.unwrap_or(LOG_INTERVAL);
+        if interval.is_zero() {
+            bail!("log interval must be greater than zero");
+        }
+        Ok(Some(RollingLogConfig {
+            path: path.clone(),
+            interval,
+            format,
+            max_bytes: self.max_bytes,
+            max_age: self.rotate_every,
+        }))
+    }
+}
+
+fn deserialize_duration<'de, D>(deserializer: D) -> Result<Option<Duration>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let Some(text) = Option::<String>::deserialize(deserializer)? else {
+        return Ok(None);
+    };
+    humantime::parse_duration(&text)
+        .map(Some)
+        .map_err(serde::de::Error::custom)
+}
+
+fn deserialize_services<'de, D>(deserializer: D) -> Result<Vec<Uuid>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Vec::<String>::deserialize(deserializer)?
+        .iter()
+        .map(|value| bleah::parse_service_uuid(value).map_err(serde::de::Error::custom))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> Config {
+        toml::from_str(text).unwrap()
+    }
+
+    #[test]
+    fn defaults_are_valid() {
+        assert!(Config::default().validate().is_ok());
+        assert!(parse("[theme]\npreset = \"light\"").validate().is_ok());
+    }
+
+    #[test]
+    fn rejects_floor_at_or_above_ceiling() {
+        let config = parse("rssi_floor = -40\nrssi_ceiling = -40");
+        let error = config.validate().unwrap_err();
+        assert!(error.to_string().contains("must be below rssi_ceiling"));
+        assert!(parse("rssi_floor = -30").validate().is_err());
+    }
+
+    #[test]
+    fn rejects_unknown_theme_preset() {
+        let error = parse("[theme]\npreset = \"neon\"").validate().unwrap_err();
+        assert_eq!(error.to_string(), "unknown theme \"neon\"");
+    }
+}
@@ -0,0 +1,206 @@
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc as std_mpsc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::runtime::Handle;
+use tokio::sync::mpsc;
+
+use crate::{
+    DeviceInfo, PeripheralDecoder, ScanMessage, ScanSink, SharedDecoders, detail_number, label_slug,
+};
+
+const QUEUE_SIZE: usize = 64;
+const MEASUREMENT: &str = "bleah";
+
+#[derive(Clone, Debug)]
+pub enum InfluxTarget {
+    File(PathBuf),
+    // Plain http only, e.g. `http://localhost:8086/write?db=bleah`.
+    Http(String),
+}
+
+impl InfluxTarget {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        if value.starts_with("http://") {
+            Ok(InfluxTarget::Http(value.to_string()))
+        } else if value.starts_with("https://") {
+            Err(format!("{value}: https is not supported, use http://"))
+        } else if value.contains("://") {
+            Err(format!("{value}: expected an http:// URL or a file path"))
+        } else {
+            Ok(InfluxTarget::File(PathBuf::from(value)))
+        }
+    }
+}
+
+pub struct InfluxSink {
+    tx: mpsc::Sender<String>,
+    decoders: SharedDecoders,
+    errors: std_mpsc::Receiver<String>,
+}
+
+impl InfluxSink {
+    pub fn spawn(runtime: &Handle, target: InfluxTarget, decoders: SharedDecoders) -> Self {
+        let (tx, mut rx) = mpsc::channel::<String>(QUEUE_SIZE);
+        // Failures come back through take_status; each distinct error is reported once.
+        let (status, errors) = std_mpsc::channel();
+        runtime.spawn(async move {
+            let mut last_error = None;
+            while let Some(batch) = rx.recv().await {
+                let result = match &target {
+                    InfluxTarget::File(path) => append(path, &batch).await,
+                    InfluxTarget::Http(url) => post(url, &batch).await,
+                };
+                match result {
+                    Ok(()) => last_error = None,
+                    Err(err) => {
+                        let message = format!("InfluxDB write failed: {err}");
+                        if last_error.as_ref() != Some(&message) {
+                            let _ = status.send(message.clone());
+                            last_error = Some(message);
+                        }
+                    }
+                }
+            }
+        });
+        Self {
+            tx,
+            decoders,
+            errors,
+        }
+    }
+}
+
+impl ScanSink for InfluxSink {
+    fn handle(&mut self, msg: &ScanMessage) {
+        let ScanMessage::Devices(devices) = msg else {
+            return;
+        };
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+        let batch = devices
+            .iter()
+            .filter_map(|device| line(device, &self.decoders, timestamp))
+            .collect::<String>();
+        if !batch.is_empty() {
+            // Drop batches rather than block when the target can't keep up.
+            let _ = self.tx.try_send(batch);
+        }
+    }
+
+    fn flush(&mut self) {}
+
+    fn take_status(&mut self) -> Option<String> {
+        self.errors.try_recv().ok()
+    }
+}
+
+fn line(
+    device: &DeviceInfo,
+    decoders: &[Box<dyn PeripheralDecoder>],
+    timestamp: u128,
+) -> Option<String> {
+    let fields = decoders
+        .iter()
+        .flat_map(|decoder| decoder.details(device))
+        .filter_map(|item| {
+            let value = detail_number(&item.value)?;
+            Some(format!("{}={value}", label_slug(&item.label)))
+        })
+        .collect::<Vec<_>>();
+    if fields.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "{MEASUREMENT},address={},name={} {} {timestamp}\n",
+        escape_tag(device.address()),
+        escape_tag(&device.name),
+        fields.join(",")
+    ))
+}
+
+fn escape_tag(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, ',' | '=' | ' ') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+async fn append(path: &Path, batch: &str) -> io::Result<()> {
+    let path = path.to_path_buf();
+    let batch = batch.to_string();
+    tokio::task::spawn_blocking(move || {
+        use std::io::Write as _;
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?
+            .write_all(batch.as_bytes())
+    })
+    .await?
+}
+
+async fn post(url: &str, batch: &str) -> io::Result<()> {
+    let rest = url.trim_start_matches("http://");
+    let (authority, path) = match rest.find('/') {
+        Some(index) => rest.split_at(index),
+        None => (rest, "/write"),
+    };
+    let address = if authority.contains(':') {
+        authority.to_string()
+    } else {
+        format!("{authority}:80")
+    };
+
+    let mut stream = TcpStream::connect(address).await?;
+    let request = format!(
+        "POST {path} HTTP/1.1\r\nHost: {authority}\r\nContent-Type: text/plain\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{batch}",
+        batch.len()
+    );
+    stream.write_all(request.as_bytes()).await?;
+    // Drain the response so the server doesn't see a reset before it has replied.
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response).await?;
+    let response = String::from_utf8_lossy(&response);
+    let status_line = response.lines().next().unwrap_or_default();
+    match status_code(status_line) {
+        Some(200..=299) => Ok(()),
+        _ => Err(io::Error::other(format!("server replied {status_line:?}"))),
+    }
+}
+
+// "HTTP/1.1 204 No Content" -> 204
+fn status_code(status_line: &str) -> Option<u16> {
+    status_line.split_whitespace().nth(1)?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn target_rejects_https() {
+        let http = InfluxTarget::parse("http://localhost:8086/write?db=bleah");
+        assert!(matches!(http, Ok(InfluxTarget::Http(_))));
+        let file = InfluxTarget::parse("readings.lp");
+        assert!(matches!(file, Ok(InfluxTarget::File(_))));
+        assert!(InfluxTarget::parse("https://localhost:8086/write").is_err());
+        assert!(InfluxTarget::parse("udp://localhost:8089").is_err());
+    }
+
+    #[test]
+    fn status_code_from_status_line() {
+        assert_eq!(status_code("HTTP/1.1 204 No Content"), Some(204));
+        assert_eq!(status_code("HTTP/1.1 401 Unauthorized"), Some(401));
+        assert_eq!(status_code(""), None);
+    }
+}
@@ -1,28 +1,311 @@
-use std::collections::BTreeMap;
-use std::sync::mpsc;
-use std::time::Duration;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs::File;
+use std::io::{self, BufWriter, Write as _};
+use std::path::Path;
+use std::pin::Pin;
+use std::sync::{Arc, mpsc};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-use btleplug::api::{AddressType, Central as _, Manager as _, Peripheral as _, ScanFilter};
-use btleplug::platform::Manager;
-use tokio::sync::watch;
+use btleplug::api::{
+    AddressType, BDAddr, Central as _, CentralEvent, CentralState, CharPropFlags, Characteristic,
+    Manager as _, Peripheral as _, ScanFilter, WriteType,
+};
+use btleplug::platform::{Adapter, Manager, Peripheral, PeripheralId};
+use futures::{FutureExt as _, Stream, StreamExt};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use tokio::sync::{mpsc as tokio_mpsc, watch};
+use uuid::Uuid;
 
-#[derive(Clone, Debug)]
+mod assigned_numbers;
+pub mod influx;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+#[cfg(feature = "mqtt")]
+pub mod mqtt;
+pub mod pcap;
+pub mod replay;
+pub mod rolling;
+pub mod sink;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct DeviceInfo {
     pub id: String,
+    // Empty where the platform hides it (CoreBluetooth) and in older recordings.
+    #[serde(default)]
+    pub address: String,
     pub name: String,
     pub rssi: Option<i16>,
+    pub rssi_smoothed: Option<i16>,
     pub connected: bool,
     pub tx_power_level: Option<i16>,
     pub address_type: Option<AddressType>,
+    pub appearance: Option<u16>,
+    pub connectable: Option<bool>,
+    #[serde(
+        serialize_with = "serialize_hex_opt",
+        deserialize_with = "deserialize_hex_opt",
+        default
+    )]
+    pub raw_adv: Option<Vec<u8>>,
+    #[serde(
+        serialize_with = "serialize_hex_map",
+        deserialize_with = "deserialize_hex_map"
+    )]
     pub manufacturer_data: BTreeMap<u16, Vec<u8>>,
+    #[serde(
+        serialize_with = "serialize_hex_map",
+        deserialize_with = "deserialize_hex_map"
+    )]
     pub service_data: BTreeMap<String, Vec<u8>>,
     pub services: Vec<String>,
+    #[serde(default)]
+    pub adv_count: u32,
+    pub first_seen: SystemTime,
+    pub last_seen: SystemTime,
+}
+
+impl DeviceInfo {
+    // The Bluetooth address when known, otherwise the platform id.
+    pub fn address(&self) -> &str {
+        if self.address.is_empty() {
+            &self.id
+        } else {
+            &self.address
+        }
+    }
 }
 
 #[derive(Debug)]
 pub enum ScanMessage {
     Devices(Vec<DeviceInfo>),
     Status(String),
+    Gatt(GattProfile),
+    HeartRate {
+        id: String,
+        bpm: u16,
+    },
+    CharacteristicValue {
+        id: String,
+        service: String,
+        characteristic: String,
+        value: Result<Vec<u8>, String>,
+    },
+}
+
+pub trait ScanSink {
+    fn handle(&mut self, msg: &ScanMessage);
+    fn flush(&mut self);
+
+    // A sink whose output went away (e.g. a closed pipe) asks the run to wind down, so the
+    // other sinks still get flushed.
+    fn finished(&self) -> bool {
+        false
+    }
+
+    // Problems a sink hits in the background, for the caller to show as a status line.
+    fn take_status(&mut self) -> Option<String> {
+        None
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum ScanCommand {
+    Connect(String),
+    Disconnect(String),
+    Read {
+        id: String,
+        service: String,
+        characteristic: String,
+    },
+    Write {
+        id: String,
+        service: String,
+        characteristic: String,
+        value: Vec<u8>,
+    },
+}
+
+#[derive(Clone, Debug)]
+pub struct GattProfile {
+    pub id: String,
+    pub services: Vec<GattService>,
+    pub battery_level: Option<u8>,
+    pub heart_rate: Option<u16>,
+    pub environment: EnvironmentReadings,
+}
+
+// Environmental Sensing Service values (or a Thingy:52's own), already scaled to C, % and
+// hPa; air quality comes only from the Thingy, as eCO2 in ppm and TVOC in ppb.
+#[derive(Clone, Debug, Default)]
+pub struct EnvironmentReadings {
+    pub temperature: Option<f32>,
+    pub humidity: Option<f32>,
+    pub pressure: Option<f32>,
+    pub co2: Option<u16>,
+    pub tvoc: Option<u16>,
+}
+
+impl GattProfile {
+    pub fn details(&self) -> Vec<DetailItem> {
+        let mut items = Vec::new();
+        if let Some(level) = self.battery_level {
+            items.push(DetailItem {
+                label: "Battery".to_string(),
+                value: format!("{level}%"),
+            });
+        }
+        if let Some(bpm) = self.heart_rate {
+            items.push(DetailItem {
+                label: "Heart rate".to_string(),
+                value: format!("{bpm} bpm"),
+            });
+        }
+        if let Some(temperature) = self.environment.temperature {
+            items.push(DetailItem {
+                label: "Temperature".to_string(),
+                value: format!("{temperature:.2} C"),
+            });
+        }
+        if let Some(humidity) = self.environment.humidity {
+            items.push(DetailItem {
+                label: "Humidity".to_string(),
+                value: format!("{humidity:.2}%"),
+            });
+        }
+        if let Some(pressure) = self.environment.pressure {
+            items.push(DetailItem {
+                label: "Pressure".to_string(),
+                value: format!("{pressure:.1} hPa"),
+            });
+        }
+        if let Some(co2) = self.environment.co2 {
+            items.push(DetailItem {
+                label: "eCO2".to_string(),
+                value: format!("{co2} ppm"),
+            });
+        }
+        if let Some(tvoc) = self.environment.tvoc {
+            items.push(DetailItem {
+                label: "TVOC".to_string(),
+                value: format!("{tvoc} ppb"),
+            });
+        }
+        items
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct GattService {
+    pub uuid: String,
+    pub primary: bool,
+    pub characteristics: Vec<GattCharacteristic>,
+}
+
+#[derive(Clone, Debug)]
+pub struct GattCharacteristic {
+    pub uuid: String,
+    pub properties: CharPropFlags,
+}
+
+#[derive(Clone, Debug, Default)]
+pub enum AdapterSelector {
+    #[default]
+    First,
+    Index(usize),
+    Name(String),
+}
+
+impl AdapterSelector {
+    fn matches(&self, index: usize, info: &str) -> bool {
+        match self {
+            AdapterSelector::First => true,
+            AdapterSelector::Index(wanted) => index == *wanted,
+            AdapterSelector::Name(name) => info.to_lowercase().contains(&name.to_lowercase()),
+        }
+    }
+}
+
+impl std::fmt::Display for AdapterSelector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AdapterSelector::First => write!(f, "first adapter"),
+            AdapterSelector::Index(index) => write!(f, "adapter #{index}"),
+            AdapterSelector::Name(name) => write!(f, "adapter matching \"{name}\""),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ScanConfig {
+    pub adapter: AdapterSelector,
+    pub poll_interval: Duration,
+    pub min_rssi: Option<i16>,
+    pub keep_unknown_rssi: bool,
+    pub name_filter: Option<String>,
+    pub device_ttl: Option<Duration>,
+    // Devices silent for this long are dropped from memory, unless they're connected.
+    pub forget_after: Duration,
+    pub rssi_alpha: f32,
+    // Passed to the adapter so filtering happens in the controller, not here.
+    pub services: Vec<Uuid>,
+}
+
+impl Default for ScanConfig {
+    fn default() -> Self {
+        Self {
+            adapter: AdapterSelector::First,
+            poll_interval: Duration::from_secs(2),
+            min_rssi: None,
+            keep_unknown_rssi: true,
+            name_filter: None,
+            device_ttl: None,
+            forget_after: Duration::from_secs(600),
+            rssi_alpha: 0.3,
+            services: Vec::new(),
+        }
+    }
+}
+
+impl ScanConfig {
+    fn smooth_rssi(&self, previous: Option<i16>, rssi: Option<i16>) -> Option<i16> {
+        match (previous, rssi) {
+            (Some(previous), Some(rssi)) => {
+                let alpha = self.rssi_alpha.clamp(0.0, 1.0);
+                let smoothed = alpha * f32::from(rssi) + (1.0 - alpha) * f32::from(previous);
+                Some(smoothed.round() as i16)
+            }
+            (None, rssi) => rssi,
+            (previous, None) => previous,
+        }
+    }
+
+    fn forgets(&self, device: &DeviceInfo, now: SystemTime) -> bool {
+        now.duration_since(device.last_seen).unwrap_or_default() > self.forget_after
+    }
+
+    fn accepts(&self, device: &DeviceInfo, now: SystemTime) -> bool {
+        if let Some(ttl) = self.device_ttl {
+            let age = now.duration_since(device.last_seen).unwrap_or_default();
+            if age > ttl {
+                return false;
+            }
+        }
+        if let Some(filter) = &self.name_filter {
+            let filter = filter.to_lowercase();
+            if !device.name.to_lowercase().contains(&filter)
+                && !device.address().to_lowercase().contains(&filter)
+            {
+                return false;
+            }
+        }
+        match (self.min_rssi, device.rssi) {
+            (None, _) => true,
+            (Some(_), None) => self.keep_unknown_rssi,
+            (Some(min_rssi), Some(rssi)) => rssi >= min_rssi,
+        }
+    }
 }
 
 pub struct DetailItem {
@@ -30,13 +313,167 @@ pub struct DetailItem {
     pub value: String,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DeviceCategory {
+    Sensor,
+    Beacon,
+    Phone,
+    Input,
+    Energy,
+    HeartRate,
+    Tracker,
+}
+
 pub trait PeripheralDecoder: Send + Sync {
+    fn name(&self) -> &str;
     fn summary(&self, device: &DeviceInfo) -> Option<String>;
     fn details(&self, device: &DeviceInfo) -> Vec<DetailItem>;
+
+    // What kind of device a matching advertisement implies, if anything.
+    fn category(&self) -> Option<DeviceCategory> {
+        None
+    }
+}
+
+pub type SharedDecoders = Arc<[Box<dyn PeripheralDecoder>]>;
+
+// Decoders in match order, each of which can be switched off by name before building.
+#[derive(Default)]
+pub struct DecoderRegistry {
+    decoders: Vec<(Box<dyn PeripheralDecoder>, bool)>,
+}
+
+impl DecoderRegistry {
+    pub fn with_victron_keys(victron_keys: HashMap<String, [u8; 16]>) -> Self {
+        // Keys are matched on the upper-case Bluetooth address.
+        let victron_keys = victron_keys
+            .into_iter()
+            .map(|(address, key)| (address.to_ascii_uppercase(), key))
+            .collect();
+        Self::default()
+            .register(Box::new(RuuviDecoder))
+            .register(Box::new(EddystoneDecoder))
+            .register(Box::new(TiltDecoder))
+            .register(Box::new(IBeaconDecoder))
+            .register(Box::new(FindMyDecoder))
+            .register(Box::new(AppleContinuityDecoder))
+            .register(Box::new(SwiftPairDecoder))
+            .register(Box::new(AltBeaconDecoder))
+            .register(Box::new(BtHomeDecoder))
+            .register(Box::new(MiFloraDecoder))
+            .register(Box::new(MiBeaconDecoder))
+            .register(Box::new(AtcDecoder))
+            .register(Box::new(GoveeDecoder))
+            .register(Box::new(GoveeH5179Decoder))
+            .register(Box::new(InkbirdDecoder))
+            .register(Box::new(SensorPushDecoder))
+            .register(Box::new(SwitchBotDecoder))
+            .register(Box::new(OralBDecoder))
+            .register(Box::new(SensorTagDecoder))
+            .register(Box::new(HeartRateDecoder))
+            .register(Box::new(ThingyDecoder))
+            .register(Box::new(NordicUartDecoder))
+            .register(Box::new(VictronDecoder { keys: victron_keys }))
+    }
+
+    pub fn register(mut self, decoder: Box<dyn PeripheralDecoder>) -> Self {
+        self.decoders.push((decoder, true));
+        self
+    }
+
+    // Names match case-insensitively; returns false when no decoder has that name.
+    pub fn set_enabled(&mut self, name: &str, enabled: bool) -> bool {
+        let mut found = false;
+        for (decoder, flag) in &mut self.decoders {
+            if decoder.name().eq_ignore_ascii_case(name) {
+                *flag = enabled;
+                found = true;
+            }
+        }
+        found
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = (&str, bool)> {
+        self.decoders
+            .iter()
+            .map(|(decoder, enabled)| (decoder.name(), *enabled))
+    }
+
+    pub fn build(self) -> Vec<Box<dyn PeripheralDecoder>> {
+        self.decoders
+            .into_iter()
+            .filter_map(|(decoder, enabled)| enabled.then_some(decoder))
+            .collect()
+    }
 }
 
-pub fn default_decoders() -> Vec<Box<dyn PeripheralDecoder>> {
-    vec![Box::new(RuuviDecoder)]
+// The first decoder's summary, along with the name of the decoder that produced it.
+pub fn summary_with_source<'a>(
+    decoders: &'a [Box<dyn PeripheralDecoder>],
+    device: &DeviceInfo,
+) -> Option<(&'a str, String)> {
+    decoders
+        .iter()
+        .find_map(|decoder| Some((decoder.name(), decoder.summary(device)?)))
+}
+
+// Only a subset of the assigned company ids is bundled, so None doesn't mean the id is
+// unassigned; callers fall back to showing it in hex.
+pub fn company_name(id: u16) -> Option<&'static str> {
+    assigned_numbers::COMPANY_IDS
+        .binary_search_by_key(&id, |(company_id, _)| *company_id)
+        .ok()
+        .map(|index| assigned_numbers::COMPANY_IDS[index].1)
+}
+
+pub fn short_uuid(uuid: &str) -> Option<u16> {
+    let uuid = Uuid::parse_str(uuid).ok()?;
+    let bytes = uuid.as_bytes();
+    let base = Uuid::from_u128(0x00000000_0000_1000_8000_00805f9b34fb);
+    if bytes[0..2] != [0, 0] || bytes[4..] != base.as_bytes()[4..] {
+        return None;
+    }
+    Some(u16::from_be_bytes([bytes[2], bytes[3]]))
+}
+
+pub fn service_name(uuid: &str) -> Option<&'static str> {
+    let id = short_uuid(uuid)?;
+    assigned_numbers::SERVICE_UUIDS
+        .binary_search_by_key(&id, |(service_id, _)| *service_id)
+        .ok()
+        .map(|index| assigned_numbers::SERVICE_UUIDS[index].1)
+}
+
+pub fn appearance_name(code: u16) -> Option<&'static str> {
+    let lookup = |code: u16| {
+        assigned_numbers::APPEARANCES
+            .binary_search_by_key(&code, |(appearance, _)| *appearance)
+            .ok()
+            .map(|index| assigned_numbers::APPEARANCES[index].1)
+    };
+    // The low 6 bits are a subcategory; fall back to the category name when it's not listed.
+    lookup(code).or_else(|| lookup(code & !0x3f))
+}
+
+// Expected RSSI at 1 m for a typical phone-class transmitter.
+pub const DEFAULT_REFERENCE_POWER: i16 = -59;
+
+pub fn estimate_distance_meters(rssi: i16, tx_power: i16) -> f32 {
+    const PATH_LOSS_EXPONENT: f32 = 2.0;
+    10f32.powf(f32::from(tx_power - rssi) / (10.0 * PATH_LOSS_EXPONENT))
+}
+
+// A rough guess from signal strength alone; `reference_power` is used when the device
+// doesn't advertise its tx power.
+pub fn estimate_distance(device: &DeviceInfo, reference_power: i16) -> Option<f32> {
+    let rssi = device.rssi_smoothed.or(device.rssi)?;
+    // Advertised tx power is measured at 0 m; the path-loss formula wants the expected RSSI
+    // at 1 m, which is roughly 41 dB lower.
+    let reference_power = device
+        .tx_power_level
+        .map(|tx_power| tx_power - 41)
+        .unwrap_or(reference_power);
+    Some(estimate_distance_meters(rssi, reference_power))
 }
 
 pub fn hex_bytes(bytes: &[u8]) -> String {
@@ -47,172 +484,4002 @@ pub fn hex_bytes(bytes: &[u8]) -> String {
         .join(" ")
 }
 
-pub async fn scan_loop(tx: mpsc::Sender<ScanMessage>, mut shutdown: watch::Receiver<bool>) {
-    let manager = match Manager::new().await {
-        Ok(manager) => manager,
-        Err(err) => {
-            let _ = tx.send(ScanMessage::Status(format!("BLE manager error: {err}")));
-            return;
+// For display: hex_bytes leaves zero-length payloads blank, which reads as missing data.
+pub fn hex_display(bytes: &[u8]) -> String {
+    if bytes.is_empty() {
+        "(empty)".to_string()
+    } else {
+        hex_bytes(bytes)
+    }
+}
+
+// Most significant bit first, for payloads where individual flag bits matter.
+pub fn bit_string(bytes: &[u8]) -> String {
+    if bytes.is_empty() {
+        return "(empty)".to_string();
+    }
+    bytes
+        .iter()
+        .map(|byte| format!("{byte:08b}"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// Inverse of hex_bytes; also tolerates a 0x prefix and ':' or '-' separators.
+pub fn parse_hex_bytes(text: &str) -> Result<Vec<u8>, String> {
+    let text = text.trim();
+    let digits = text
+        .strip_prefix("0x")
+        .unwrap_or(text)
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != ':' && *c != '-')
+        .collect::<Vec<_>>();
+    if digits.len() % 2 != 0 {
+        return Err("odd number of hex digits".to_string());
+    }
+    digits
+        .chunks(2)
+        .map(|pair| {
+            let pair = pair.iter().collect::<String>();
+            u8::from_str_radix(&pair, 16).map_err(|_| format!("invalid hex byte {pair:?}"))
+        })
+        .collect()
+}
+
+pub fn hex_dump(bytes: &[u8]) -> String {
+    bytes
+        .chunks(16)
+        .enumerate()
+        .map(|(row, chunk)| {
+            format!(
+                "{:08x}: {:<47}  {}",
+                row * 16,
+                hex_bytes(chunk),
+                ascii_bytes(chunk)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AdStructure {
+    Flags(u8),
+    ServiceUuids16 { complete: bool, uuids: Vec<u16> },
+    ServiceUuids32 { complete: bool, uuids: Vec<u32> },
+    ServiceUuids128 { complete: bool, uuids: Vec<Uuid> },
+    LocalName { complete: bool, name: String },
+    TxPowerLevel(i8),
+    Appearance(u16),
+    ServiceData16 { uuid: u16, data: Vec<u8> },
+    ServiceData128 { uuid: Uuid, data: Vec<u8> },
+    ManufacturerData { company_id: u16, data: Vec<u8> },
+    Other { ad_type: u8, data: Vec<u8> },
+}
+
+impl AdStructure {
+    pub fn label(&self) -> &'static str {
+        match self {
+            AdStructure::Flags(_) => "Flags",
+            AdStructure::ServiceUuids16 { complete: true, .. } => "Complete 16-bit UUIDs",
+            AdStructure::ServiceUuids16 { .. } => "Incomplete 16-bit UUIDs",
+            AdStructure::ServiceUuids32 { complete: true, .. } => "Complete 32-bit UUIDs",
+            AdStructure::ServiceUuids32 { .. } => "Incomplete 32-bit UUIDs",
+            AdStructure::ServiceUuids128 { complete: true, .. } => "Complete 128-bit UUIDs",
+            AdStructure::ServiceUuids128 { .. } => "Incomplete 128-bit UUIDs",
+            AdStructure::LocalName { complete: true, .. } => "Complete Local Name",
+            AdStructure::LocalName { .. } => "Shortened Local Name",
+            AdStructure::TxPowerLevel(_) => "TX Power Level",
+            AdStructure::Appearance(_) => "Appearance",
+            AdStructure::ServiceData16 { .. } => "Service Data (16-bit)",
+            AdStructure::ServiceData128 { .. } => "Service Data (128-bit)",
+            AdStructure::ManufacturerData { .. } => "Manufacturer Data",
+            AdStructure::Other { .. } => "Unknown AD type",
         }
-    };
+    }
 
-    let adapters = match manager.adapters().await {
-        Ok(adapters) => adapters,
-        Err(err) => {
-            let _ = tx.send(ScanMessage::Status(format!("Adapter discovery error: {err}")));
-            return;
+    pub fn value(&self) -> String {
+        let join = |items: Vec<String>| items.join(", ");
+        match self {
+            AdStructure::Flags(flags) => {
+                let names = [
+                    "LE Limited Discoverable",
+                    "LE General Discoverable",
+                    "BR/EDR Not Supported",
+                    "LE and BR/EDR Controller",
+                    "LE and BR/EDR Host",
+                ];
+                let set = names
+                    .iter()
+                    .enumerate()
+                    .filter(|(bit, _)| flags & (1 << bit) != 0)
+                    .map(|(_, name)| name.to_string())
+                    .collect();
+                format!("0x{flags:02x} ({})", join(set))
+            }
+            AdStructure::ServiceUuids16 { uuids, .. } => {
+                join(uuids.iter().map(|uuid| format!("0x{uuid:04X}")).collect())
+            }
+            AdStructure::ServiceUuids32 { uuids, .. } => {
+                join(uuids.iter().map(|uuid| format!("0x{uuid:08X}")).collect())
+            }
+            AdStructure::ServiceUuids128 { uuids, .. } => {
+                join(uuids.iter().map(Uuid::to_string).collect())
+            }
+            AdStructure::LocalName { name, .. } => name.clone(),
+            AdStructure::TxPowerLevel(level) => format!("{level} dBm"),
+            AdStructure::Appearance(code) => match appearance_name(*code) {
+                Some(name) => format!("{name} (0x{code:04X})"),
+                None => format!("0x{code:04X}"),
+            },
+            AdStructure::ServiceData16 { uuid, data } => {
+                format!("0x{uuid:04X}: {}", hex_display(data))
+            }
+            AdStructure::ServiceData128 { uuid, data } => format!("{uuid}: {}", hex_display(data)),
+            AdStructure::ManufacturerData { company_id, data } => {
+                let company = company_name(*company_id)
+                    .map_or_else(|| format!("0x{company_id:04X}"), str::to_string);
+                format!("{company}: {}", hex_display(data))
+            }
+            AdStructure::Other { ad_type, data } => {
+                format!("type 0x{ad_type:02x}: {}", hex_display(data))
+            }
         }
-    };
+    }
+}
 
-    let Some(adapter) = adapters.into_iter().next() else {
-        let _ = tx.send(ScanMessage::Status("No BLE adapters found".to_string()));
-        return;
-    };
+// Walks the length/type/value fields of advertising (or scan response) data. Parsing stops
+// at a zero length, which marks padding, or at a field running past the end.
+pub fn parse_ad_structures(bytes: &[u8]) -> Vec<AdStructure> {
+    let mut structures = Vec::new();
+    let mut rest = bytes;
+    while let Some((&len, tail)) = rest.split_first() {
+        let len = usize::from(len);
+        if len == 0 || tail.len() < len {
+            break;
+        }
+        let (field, tail) = tail.split_at(len);
+        rest = tail;
+        let (ad_type, data) = (field[0], &field[1..]);
+        structures.push(parse_ad_structure(ad_type, data));
+    }
+    structures
+}
 
-    if let Err(err) = adapter.start_scan(ScanFilter::default()).await {
-        let _ = tx.send(ScanMessage::Status(format!("Scan failed: {err}")));
-        return;
+fn parse_ad_structure(ad_type: u8, data: &[u8]) -> AdStructure {
+    let other = || AdStructure::Other {
+        ad_type,
+        data: data.to_vec(),
+    };
+    let complete = ad_type % 2 == 1;
+    match ad_type {
+        0x01 if data.len() == 1 => AdStructure::Flags(data[0]),
+        0x02 | 0x03 => AdStructure::ServiceUuids16 {
+            complete,
+            uuids: data
+                .chunks_exact(2)
+                .map(|chunk| u16::from_le_bytes([chunk[0], chunk[1]]))
+                .collect(),
+        },
+        0x04 | 0x05 => AdStructure::ServiceUuids32 {
+            complete,
+            uuids: data
+                .chunks_exact(4)
+                .map(|chunk| u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+                .collect(),
+        },
+        0x06 | 0x07 => AdStructure::ServiceUuids128 {
+            complete,
+            uuids: data.chunks_exact(16).map(uuid_from_le).collect(),
+        },
+        0x08 | 0x09 => AdStructure::LocalName {
+            complete: ad_type == 0x09,
+            name: String::from_utf8_lossy(data).into_owned(),
+        },
+        0x0a if data.len() == 1 => AdStructure::TxPowerLevel(data[0] as i8),
+        0x16 if data.len() >= 2 => AdStructure::ServiceData16 {
+            uuid: u16::from_le_bytes([data[0], data[1]]),
+            data: data[2..].to_vec(),
+        },
+        0x19 if data.len() == 2 => AdStructure::Appearance(u16::from_le_bytes([data[0], data[1]])),
+        0x21 if data.len() >= 16 => AdStructure::ServiceData128 {
+            uuid: uuid_from_le(&data[..16]),
+            data: data[16..].to_vec(),
+        },
+        0xff if data.len() >= 2 => AdStructure::ManufacturerData {
+            company_id: u16::from_le_bytes([data[0], data[1]]),
+            data: data[2..].to_vec(),
+        },
+        _ => other(),
     }
+}
 
-    let mut interval = tokio::time::interval(Duration::from_secs(2));
+// 128-bit UUIDs go on the air least significant byte first.
+fn uuid_from_le(bytes: &[u8]) -> Uuid {
+    let mut raw = [0u8; 16];
+    raw.copy_from_slice(bytes);
+    Uuid::from_u128(u128::from_le_bytes(raw))
+}
 
-    loop {
-        tokio::select! {
-            _ = shutdown.changed() => {
-                if *shutdown.borrow() {
-                    break;
-                }
-            }
-            _ = interval.tick() => {
-                let peripherals = match adapter.peripherals().await {
-                    Ok(peripherals) => peripherals,
-                    Err(err) => {
-                        let _ = tx.send(ScanMessage::Status(format!("Scan error: {err}")));
-                        continue;
-                    }
-                };
+pub fn label_slug(label: &str) -> String {
+    label
+        .chars()
+        .map(|c| match c {
+            'a'..='z' | '0'..='9' => c,
+            'A'..='Z' => c.to_ascii_lowercase(),
+            _ => '_',
+        })
+        .collect()
+}
 
-                let mut devices = Vec::new();
-                for peripheral in peripherals {
-                    let id = peripheral.id().to_string();
-                    let props = peripheral.properties().await.ok().flatten();
-                    let name = props
-                        .as_ref()
-                        .and_then(|props| props.local_name.clone())
-                        .unwrap_or_else(|| "Unknown".to_string());
-                    let rssi = props.as_ref().and_then(|props| props.rssi);
-                    let connected = peripheral.is_connected().await.unwrap_or(false);
-                    let tx_power_level = props.as_ref().and_then(|props| props.tx_power_level);
-                    let address_type = props.as_ref().and_then(|props| props.address_type);
-                    let manufacturer_data = props
-                        .as_ref()
-                        .map(|props| {
-                            props
-                                .manufacturer_data
-                                .iter()
-                                .map(|(key, value)| (*key, value.clone()))
-                                .collect::<BTreeMap<_, _>>()
-                        })
-                        .unwrap_or_default();
-                    let service_data = props
-                        .as_ref()
-                        .map(|props| {
-                            props
-                                .service_data
-                                .iter()
-                                .map(|(key, value)| (key.to_string(), value.clone()))
-                                .collect::<BTreeMap<_, _>>()
-                        })
-                        .unwrap_or_default();
-                    let services = props
-                        .as_ref()
-                        .map(|props| props.services.iter().map(|uuid| uuid.to_string()).collect())
-                        .unwrap_or_default();
-
-                    devices.push(DeviceInfo {
-                        id,
-                        name,
-                        rssi,
-                        connected,
-                        tx_power_level,
-                        address_type,
-                        manufacturer_data,
-                        service_data,
-                        services,
-                    });
-                }
+// Detail values carry their unit ("21.5 C", "87%"), so keep only the leading number.
+pub(crate) fn detail_number(value: &str) -> Option<f64> {
+    let number = value.split_whitespace().next()?.trim_end_matches('%');
+    number.parse::<f64>().ok().filter(|value| value.is_finite())
+}
+
+// Coarse, single-unit relative age for table cells: "now", "42s", "5m", "3h", "2d".
+pub fn format_age(age: Duration) -> String {
+    let secs = age.as_secs();
+    match secs {
+        0 => "now".to_string(),
+        1..=59 => format!("{secs}s"),
+        60..=3599 => format!("{}m", secs / 60),
+        3600..=86399 => format!("{}h", secs / 3600),
+        _ => format!("{}d", secs / 86400),
+    }
+}
 
-                let _ = tx.send(ScanMessage::Devices(devices));
+// Phones rotate their random (resolvable private) address every few minutes, so the same
+// handset shows up as a string of short-lived devices. Flag random-address devices whose
+// manufacturer and service payloads are identical to another one seen within `window`.
+pub fn rotating_addresses(devices: &[DeviceInfo], window: Duration) -> HashSet<String> {
+    let mut payloads: HashMap<_, Vec<&DeviceInfo>> = HashMap::new();
+    for device in devices {
+        if device.address_type != Some(AddressType::Random)
+            || (device.manufacturer_data.is_empty() && device.service_data.is_empty())
+        {
+            continue;
+        }
+        payloads
+            .entry((&device.manufacturer_data, &device.service_data))
+            .or_default()
+            .push(device);
+    }
+    let mut rotating = HashSet::new();
+    for mut group in payloads.into_values() {
+        group.sort_by_key(|device| device.last_seen);
+        for pair in group.windows(2) {
+            let gap = pair[1]
+                .last_seen
+                .duration_since(pair[0].last_seen)
+                .unwrap_or_default();
+            if gap <= window {
+                rotating.insert(pair[0].id.clone());
+                rotating.insert(pair[1].id.clone());
             }
         }
     }
+    rotating
 }
 
-struct RuuviDecoder;
+// Accepts a full UUID or a 16-bit SIG short form such as "181a" or "0x181A".
+pub fn parse_service_uuid(value: &str) -> Result<Uuid, String> {
+    let short = value.trim_start_matches("0x").trim_start_matches("0X");
+    if short.len() == 4
+        && let Ok(short) = u16::from_str_radix(short, 16)
+    {
+        return Ok(Uuid::from_u128(
+            (u128::from(short) << 96) | 0x0000_0000_0000_1000_8000_0080_5f9b_34fb,
+        ));
+    }
+    Uuid::parse_str(value).map_err(|err| format!("invalid service UUID {value:?}: {err}"))
+}
 
-impl RuuviDecoder {
-    fn decode_format5(data: &[u8]) -> Option<(Option<f32>, Option<f32>)> {
-        if data.len() < 5 || data[0] != 0x05 {
-            return None;
-        }
+pub fn ascii_bytes(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|byte| {
+            if byte.is_ascii_graphic() || *byte == b' ' {
+                char::from(*byte)
+            } else {
+                '.'
+            }
+        })
+        .collect()
+}
 
-        let temp_raw = i16::from_be_bytes([data[1], data[2]]);
-        let humidity_raw = u16::from_be_bytes([data[3], data[4]]);
-        let temp = if temp_raw == i16::MIN {
-            None
-        } else {
-            Some(f32::from(temp_raw) * 0.005)
-        };
-        let humidity = if humidity_raw == u16::MAX {
-            None
-        } else {
-            Some(f32::from(humidity_raw) * 0.0025)
-        };
-        Some((temp, humidity))
+fn serialize_hex_map<K, S>(map: &BTreeMap<K, Vec<u8>>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    K: Serialize,
+    S: Serializer,
+{
+    serializer.collect_map(map.iter().map(|(key, value)| (key, hex_bytes(value))))
+}
+
+fn serialize_hex_opt<S>(bytes: &Option<Vec<u8>>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match bytes {
+        Some(bytes) => serializer.serialize_some(&hex_bytes(bytes)),
+        None => serializer.serialize_none(),
     }
 }
 
-impl PeripheralDecoder for RuuviDecoder {
-    fn summary(&self, device: &DeviceInfo) -> Option<String> {
-        let data = device.manufacturer_data.get(&0x0499)?;
-        let (temp, humidity) = Self::decode_format5(data)?;
-        let mut parts = Vec::new();
-        if let Some(temp) = temp {
-            parts.push(format!("{temp:.1} C"));
-        }
-        if let Some(humidity) = humidity {
-            parts.push(format!("{humidity:.1}%"));
-        }
-        if parts.is_empty() {
-            None
-        } else {
-            Some(parts.join(" "))
+fn deserialize_hex_map<'de, K, D>(deserializer: D) -> Result<BTreeMap<K, Vec<u8>>, D::Error>
+where
+    K: Deserialize<'de> + Ord,
+    D: Deserializer<'de>,
+{
+    BTreeMap::<K, String>::deserialize(deserializer)?
+        .into_iter()
+        .map(|(key, value)| {
+            let bytes = parse_hex_bytes(&value).map_err(serde::de::Error::custom)?;
+            Ok((key, bytes))
+        })
+        .collect()
+}
+
+fn deserialize_hex_opt<'de, D>(deserializer: D) -> Result<Option<Vec<u8>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Option::<String>::deserialize(deserializer)?
+        .map(|value| parse_hex_bytes(&value).map_err(serde::de::Error::custom))
+        .transpose()
+}
+
+#[derive(Serialize)]
+pub struct DeviceRecord<'a> {
+    pub timestamp: f64,
+    #[serde(flatten)]
+    pub device: &'a DeviceInfo,
+    pub summary: Option<String>,
+    pub decoded: BTreeMap<String, String>,
+}
+
+impl<'a> DeviceRecord<'a> {
+    pub fn new(
+        device: &'a DeviceInfo,
+        decoders: &[Box<dyn PeripheralDecoder>],
+        now: SystemTime,
+    ) -> Self {
+        let timestamp = now
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs_f64();
+        let summary = decoders.iter().find_map(|decoder| decoder.summary(device));
+        let decoded = decoders
+            .iter()
+            .flat_map(|decoder| decoder.details(device))
+            .map(|item| (item.label, item.value))
+            .collect();
+        Self {
+            timestamp,
+            device,
+            summary,
+            decoded,
         }
     }
+}
 
-    fn details(&self, device: &DeviceInfo) -> Vec<DetailItem> {
-        let data = match device.manufacturer_data.get(&0x0499) {
-            Some(data) => data,
-            None => return Vec::new(),
-        };
-        let (temp, humidity) = match Self::decode_format5(data) {
-            Some(values) => values,
-            None => return Vec::new(),
-        };
+pub fn write_json(devices: &[DeviceInfo], path: &Path) -> io::Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    serde_json::to_writer_pretty(&mut writer, devices)?;
+    writer.flush()
+}
 
-        let mut details = Vec::new();
-        if let Some(temp) = temp {
-            details.push(DetailItem {
-                label: "Ruuvi temperature".to_string(),
-                value: format!("{temp:.1} C"),
-            });
-        }
-        if let Some(humidity) = humidity {
-            details.push(DetailItem {
-                label: "Ruuvi humidity".to_string(),
-                value: format!("{humidity:.1}%"),
-            });
-        }
-        details
+pub(crate) const CSV_HEADER: &str =
+    "address,name,rssi,connected,tx_power,summary,manufacturer_data,service_data";
+
+pub fn write_csv(
+    devices: &[DeviceInfo],
+    decoders: &[Box<dyn PeripheralDecoder>],
+    path: &Path,
+) -> io::Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    writeln!(writer, "{CSV_HEADER}")?;
+    for device in devices {
+        writeln!(writer, "{}", csv_record(device, decoders))?;
+    }
+    writer.flush()
+}
+
+pub(crate) fn csv_record(device: &DeviceInfo, decoders: &[Box<dyn PeripheralDecoder>]) -> String {
+    let summary = decoders.iter().find_map(|decoder| decoder.summary(device));
+    let manufacturer_data = device
+        .manufacturer_data
+        .iter()
+        .map(|(company_id, data)| format!("0x{company_id:04x}={}", hex_bytes(data)))
+        .collect::<Vec<_>>()
+        .join("; ");
+    let service_data = device
+        .service_data
+        .iter()
+        .map(|(uuid, data)| format!("{uuid}={}", hex_bytes(data)))
+        .collect::<Vec<_>>()
+        .join("; ");
+    let fields = [
+        device.address().to_string(),
+        device.name.clone(),
+        device.rssi.map(|rssi| rssi.to_string()).unwrap_or_default(),
+        device.connected.to_string(),
+        device
+            .tx_power_level
+            .map(|tx_power| tx_power.to_string())
+            .unwrap_or_default(),
+        summary.unwrap_or_default(),
+        manufacturer_data,
+        service_data,
+    ];
+    fields
+        .iter()
+        .map(|field| csv_field(field))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn be_u16(data: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_be_bytes([*data.get(offset)?, *data.get(offset + 1)?]))
+}
+
+fn be_i16(data: &[u8], offset: usize) -> Option<i16> {
+    Some(i16::from_be_bytes([*data.get(offset)?, *data.get(offset + 1)?]))
+}
+
+pub async fn list_adapters() -> Result<Vec<String>, String> {
+    let adapters = adapters_with_info().await?;
+    Ok(adapters.into_iter().map(|(info, _)| info).collect())
+}
+
+async fn adapters_with_info() -> Result<Vec<(String, Adapter)>, String> {
+    let manager = Manager::new()
+        .await
+        .map_err(|err| format!("BLE manager error: {err}"))?;
+    let adapters = manager
+        .adapters()
+        .await
+        .map_err(|err| format!("Adapter discovery error: {err}"))?;
+    if adapters.is_empty() {
+        return Err("No BLE adapters found".to_string());
+    }
+
+    let mut result = Vec::with_capacity(adapters.len());
+    for adapter in adapters {
+        let info = adapter
+            .adapter_info()
+            .await
+            .unwrap_or_else(|_| "unknown adapter".to_string());
+        result.push((info, adapter));
+    }
+    Ok(result)
+}
+
+const MAX_CONSECUTIVE_ERRORS: u32 = 5;
+const MAX_RESTART_DELAY: Duration = Duration::from_secs(30);
+
+struct ScanSession {
+    adapter: Adapter,
+    events: Pin<Box<dyn Stream<Item = CentralEvent> + Send>>,
+}
+
+async fn start_session(config: &ScanConfig, tx: &mpsc::Sender<ScanMessage>) -> Option<ScanSession> {
+    let adapters = match adapters_with_info().await {
+        Ok(adapters) => adapters,
+        Err(err) => {
+            let _ = tx.send(ScanMessage::Status(err));
+            return None;
+        }
+    };
+
+    let selected = adapters
+        .into_iter()
+        .enumerate()
+        .find(|(index, (info, _))| config.adapter.matches(*index, info))
+        .map(|(index, (info, adapter))| (index, info, adapter));
+    let Some((index, info, adapter)) = selected else {
+        let _ = tx.send(ScanMessage::Status(format!(
+            "No BLE adapter found for {}",
+            config.adapter
+        )));
+        return None;
+    };
+    let _ = tx.send(ScanMessage::Status(format!("Using adapter {index}: {info}")));
+
+    let events = match adapter.events().await {
+        Ok(events) => events,
+        Err(err) => {
+            let _ = tx.send(ScanMessage::Status(format!("Event stream error: {err}")));
+            return None;
+        }
+    };
+
+    let filter = ScanFilter {
+        services: config.services.clone(),
+    };
+    if let Err(err) = adapter.start_scan(filter).await {
+        let _ = tx.send(ScanMessage::Status(format!("Scan failed: {err}")));
+        return None;
+    }
+    Some(ScanSession { adapter, events })
+}
+
+// Backs off exponentially until the adapter comes back; None means shutdown was requested.
+async fn restart_session(
+    config: &ScanConfig,
+    tx: &mpsc::Sender<ScanMessage>,
+    shutdown: &mut watch::Receiver<bool>,
+) -> Option<ScanSession> {
+    let mut delay = Duration::from_secs(1);
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+        let _ = tx.send(ScanMessage::Status(format!(
+            "Adapter lost, retrying in {}s (attempt {attempt})",
+            delay.as_secs()
+        )));
+        tokio::select! {
+            _ = tokio::time::sleep(delay) => {}
+            changed = shutdown.changed() => {
+                if changed.is_err() || *shutdown.borrow() {
+                    return None;
+                }
+            }
+        }
+        if let Some(session) = start_session(config, tx).await {
+            return Some(session);
+        }
+        delay = (delay * 2).min(MAX_RESTART_DELAY);
+    }
+}
+
+pub async fn scan_loop(
+    config: ScanConfig,
+    tx: mpsc::Sender<ScanMessage>,
+    mut commands: tokio_mpsc::UnboundedReceiver<ScanCommand>,
+    mut shutdown: watch::Receiver<bool>,
+) {
+    // Only a session that worked once is worth retrying; failing at startup is reported
+    // and ends the scan.
+    let Some(mut session) = start_session(&config, &tx).await else {
+        return;
+    };
+
+    let mut interval = tokio::time::interval(config.poll_interval);
+    let mut devices: HashMap<String, DeviceInfo> = HashMap::new();
+    let mut peripherals: HashMap<String, Peripheral> = HashMap::new();
+    let mut connections: HashSet<String> = HashSet::new();
+    // Connect tasks report the ids that failed so they aren't disconnected at shutdown.
+    let (failed_tx, mut failed_rx) = tokio_mpsc::unbounded_channel::<String>();
+    let mut consecutive_errors = 0;
+    let mut lost = false;
+
+    loop {
+        if lost {
+            // Peripheral handles belong to the old adapter; known devices are kept.
+            lost = false;
+            consecutive_errors = 0;
+            peripherals.clear();
+            connections.clear();
+            match restart_session(&config, &tx, &mut shutdown).await {
+                Some(restarted) => {
+                    session = restarted;
+                    let _ = tx.send(ScanMessage::Status("Scan restarted".to_string()));
+                }
+                None => break,
+            }
+        }
+
+        tokio::select! {
+            _ = shutdown.changed() => {
+                if *shutdown.borrow() {
+                    for id in connections.drain() {
+                        if let Some(peripheral) = peripherals.get(&id) {
+                            disconnect_gatt(peripheral).await;
+                        }
+                    }
+                    break;
+                }
+            }
+            event = session.events.next() => {
+                let Some(event) = event else {
+                    let _ = tx.send(ScanMessage::Status("Event stream closed".to_string()));
+                    lost = true;
+                    continue;
+                };
+                // bluez reports one advertisement as an event per changed property, so take
+                // whatever is already queued along with it and count each device once.
+                let mut burst = vec![event];
+                while let Some(next) = session.events.next().now_or_never() {
+                    match next {
+                        Some(event) => burst.push(event),
+                        None => {
+                            let _ = tx.send(ScanMessage::Status("Event stream closed".to_string()));
+                            lost = true;
+                            break;
+                        }
+                    }
+                }
+                // Per device: whether it advertised, and whether that carried a new RSSI sample
+                // (bluez signals RSSI changes as DeviceUpdated, not with the data events).
+                let mut updated: Vec<(PeripheralId, bool, bool)> = Vec::new();
+                for event in burst {
+                    let (id, advertised, sampled) = match event {
+                        CentralEvent::DeviceConnected(id)
+                        | CentralEvent::DeviceDisconnected(id) => (id, false, false),
+                        CentralEvent::DeviceDiscovered(id)
+                        | CentralEvent::DeviceUpdated(id) => (id, true, true),
+                        CentralEvent::ManufacturerDataAdvertisement { id, .. }
+                        | CentralEvent::ServiceDataAdvertisement { id, .. }
+                        | CentralEvent::ServicesAdvertisement { id, .. } => (id, true, false),
+                        CentralEvent::StateUpdate(CentralState::PoweredOff) => {
+                            let _ = tx.send(ScanMessage::Status("Adapter powered off".to_string()));
+                            lost = true;
+                            continue;
+                        }
+                        CentralEvent::StateUpdate(_) => continue,
+                    };
+                    match updated.iter_mut().find(|(seen, ..)| *seen == id) {
+                        Some((_, seen_advertised, seen_sampled)) => {
+                            *seen_advertised |= advertised;
+                            *seen_sampled |= sampled;
+                        }
+                        None => updated.push((id, advertised, sampled)),
+                    }
+                }
+
+                for (id, advertised, sampled) in updated {
+                    let Ok(peripheral) = session.adapter.peripheral(&id).await else {
+                        consecutive_errors += 1;
+                        lost |= consecutive_errors >= MAX_CONSECUTIVE_ERRORS;
+                        continue;
+                    };
+                    consecutive_errors = 0;
+
+                    let mut device = read_device(&peripheral, SystemTime::now()).await;
+                    if let Some(prev) = devices.get(&device.id) {
+                        device.first_seen = prev.first_seen;
+                        device.adv_count = prev.adv_count.saturating_add(u32::from(advertised));
+                        // Folding the same reading in again would skew the average.
+                        device.rssi_smoothed = if sampled {
+                            config.smooth_rssi(prev.rssi_smoothed, device.rssi)
+                        } else {
+                            prev.rssi_smoothed.or(device.rssi)
+                        };
+                    }
+                    peripherals.insert(device.id.clone(), peripheral);
+                    devices.insert(device.id.clone(), device);
+                }
+            }
+            Some(id) = failed_rx.recv() => {
+                connections.remove(&id);
+            }
+            Some(command) = commands.recv() => match command {
+                ScanCommand::Connect(id) => {
+                    let Some(peripheral) = peripherals.get(&id).cloned() else {
+                        let _ = tx.send(ScanMessage::Status(format!("Unknown device {id}")));
+                        continue;
+                    };
+                    connections.insert(id.clone());
+                    // Connecting can take seconds; keep handling advertisements meanwhile.
+                    let tx = tx.clone();
+                    let failed = failed_tx.clone();
+                    tokio::spawn(async move {
+                        let (services, battery_level, environment) = match connect_gatt(&peripheral).await {
+                            Ok(profile) => profile,
+                            Err(err) => {
+                                let _ = tx.send(ScanMessage::Status(format!(
+                                    "Connect to {id} failed: {err}"
+                                )));
+                                let _ = failed.send(id);
+                                return;
+                            }
+                        };
+                        let _ = tx.send(ScanMessage::Gatt(GattProfile {
+                            id: id.clone(),
+                            services,
+                            battery_level,
+                            heart_rate: None,
+                            environment,
+                        }));
+                        if let Err(err) = stream_heart_rate(&peripheral, &id, &tx).await {
+                            let _ = tx.send(ScanMessage::Status(format!(
+                                "Heart rate subscription failed: {err}"
+                            )));
+                        }
+                    });
+                }
+                ScanCommand::Disconnect(id) => {
+                    connections.remove(&id);
+                    if let Some(peripheral) = peripherals.get(&id).cloned() {
+                        tokio::spawn(async move {
+                            disconnect_gatt(&peripheral).await;
+                        });
+                    }
+                }
+                ScanCommand::Read { id, service, characteristic } => {
+                    let Some(peripheral) = peripherals.get(&id).cloned() else {
+                        let _ = tx.send(ScanMessage::Status(format!("Unknown device {id}")));
+                        continue;
+                    };
+                    let tx = tx.clone();
+                    tokio::spawn(async move {
+                        let found = find_characteristic(&peripheral, &service, &characteristic);
+                        let value = match found {
+                            Some(found) => peripheral.read(&found).await,
+                            None => Err(btleplug::Error::NoSuchCharacteristic),
+                        };
+                        let value = value.map_err(|err| err.to_string());
+                        let _ = tx.send(ScanMessage::CharacteristicValue {
+                            id,
+                            service,
+                            characteristic,
+                            value,
+                        });
+                    });
+                }
+                ScanCommand::Write { id, service, characteristic, value } => {
+                    let Some(peripheral) = peripherals.get(&id).cloned() else {
+                        let _ = tx.send(ScanMessage::Status(format!("Unknown device {id}")));
+                        continue;
+                    };
+                    let tx = tx.clone();
+                    tokio::spawn(async move {
+                        let result =
+                            write_characteristic(&peripheral, &service, &characteristic, &value)
+                                .await;
+                        let label = match short_uuid(&characteristic) {
+                            Some(short) => format!("0x{short:04X}"),
+                            None => characteristic,
+                        };
+                        let status = match result {
+                            Ok(()) => format!("Wrote {} bytes to {label}", value.len()),
+                            Err(err) => format!("Write to {label} failed: {err}"),
+                        };
+                        let _ = tx.send(ScanMessage::Status(status));
+                    });
+                }
+            },
+            _ = interval.tick() => {
+                // Unplugging the adapter doesn't always end the event stream (bluez just goes
+                // quiet), so check on it directly.
+                match session.adapter.adapter_state().await {
+                    Ok(CentralState::PoweredOff) | Err(_) => {
+                        let _ = tx.send(ScanMessage::Status("Adapter unavailable".to_string()));
+                        lost = true;
+                        continue;
+                    }
+                    Ok(_) => {}
+                }
+                let now = SystemTime::now();
+                // Without this a long scan in a busy place (rotating addresses) grows forever.
+                devices.retain(|id, device| connections.contains(id) || !config.forgets(device, now));
+                peripherals.retain(|id, _| devices.contains_key(id));
+                let snapshot = devices
+                    .values()
+                    .filter(|device| config.accepts(device, now))
+                    .cloned()
+                    .collect();
+                let _ = tx.send(ScanMessage::Devices(snapshot));
+            }
+        }
+    }
+}
+
+const BATTERY_SERVICE_UUID: &str = "0000180f-0000-1000-8000-00805f9b34fb";
+const BATTERY_LEVEL_CHARACTERISTIC_UUID: &str = "00002a19-0000-1000-8000-00805f9b34fb";
+const HEART_RATE_SERVICE_UUID: &str = "0000180d-0000-1000-8000-00805f9b34fb";
+const HEART_RATE_MEASUREMENT_CHARACTERISTIC_UUID: &str = "00002a37-0000-1000-8000-00805f9b34fb";
+const PRESSURE_CHARACTERISTIC_UUID: &str = "00002a6d-0000-1000-8000-00805f9b34fb";
+const TEMPERATURE_CHARACTERISTIC_UUID: &str = "00002a6e-0000-1000-8000-00805f9b34fb";
+const HUMIDITY_CHARACTERISTIC_UUID: &str = "00002a6f-0000-1000-8000-00805f9b34fb";
+
+async fn connect_gatt(
+    peripheral: &Peripheral,
+) -> btleplug::Result<(Vec<GattService>, Option<u8>, EnvironmentReadings)> {
+    if !peripheral.is_connected().await? {
+        peripheral.connect().await?;
+    }
+    peripheral.discover_services().await?;
+
+    let mut battery_level = None;
+    if let Some(characteristic) = find_characteristic(
+        peripheral,
+        BATTERY_SERVICE_UUID,
+        BATTERY_LEVEL_CHARACTERISTIC_UUID,
+    ) {
+        battery_level = peripheral
+            .read(&characteristic)
+            .await
+            .ok()
+            .and_then(|value| value.first().copied());
+    }
+
+    let services = peripheral
+        .services()
+        .into_iter()
+        .map(|service| GattService {
+            uuid: service.uuid.to_string(),
+            primary: service.primary,
+            characteristics: service
+                .characteristics
+                .into_iter()
+                .map(|characteristic| GattCharacteristic {
+                    uuid: characteristic.uuid.to_string(),
+                    properties: characteristic.properties,
+                })
+                .collect(),
+        })
+        .collect();
+    let mut environment = read_environment(peripheral).await;
+    read_thingy_environment(peripheral, &mut environment).await;
+    Ok((services, battery_level, environment))
+}
+
+async fn read_environment(peripheral: &Peripheral) -> EnvironmentReadings {
+    let read = |characteristic_uuid| async move {
+        let characteristic = find_characteristic(
+            peripheral,
+            ENVIRONMENTAL_SENSING_SERVICE_UUID,
+            characteristic_uuid,
+        )?;
+        peripheral.read(&characteristic).await.ok()
+    };
+    // Temperature is sint16 in 0.01 C, humidity uint16 in 0.01 %, pressure uint32 in 0.1 Pa.
+    let temperature = read(TEMPERATURE_CHARACTERISTIC_UUID)
+        .await
+        .and_then(|value| {
+            let raw = i16::from_le_bytes(value.get(..2)?.try_into().ok()?);
+            Some(f32::from(raw) / 100.0)
+        });
+    let humidity = read(HUMIDITY_CHARACTERISTIC_UUID).await.and_then(|value| {
+        let raw = u16::from_le_bytes(value.get(..2)?.try_into().ok()?);
+        Some(f32::from(raw) / 100.0)
+    });
+    let pressure = read(PRESSURE_CHARACTERISTIC_UUID).await.and_then(|value| {
+        let raw = u32::from_le_bytes(value.get(..4)?.try_into().ok()?);
+        Some(raw as f32 / 1000.0)
+    });
+    EnvironmentReadings {
+        temperature,
+        humidity,
+        pressure,
+        ..EnvironmentReadings::default()
+    }
+}
+
+const THINGY_ENVIRONMENT_SERVICE_UUID: &str = "ef680200-9b35-4933-9b10-52ffa9740042";
+const THINGY_TEMPERATURE_CHARACTERISTIC_UUID: &str = "ef680201-9b35-4933-9b10-52ffa9740042";
+const THINGY_PRESSURE_CHARACTERISTIC_UUID: &str = "ef680202-9b35-4933-9b10-52ffa9740042";
+const THINGY_HUMIDITY_CHARACTERISTIC_UUID: &str = "ef680203-9b35-4933-9b10-52ffa9740042";
+const THINGY_GAS_CHARACTERISTIC_UUID: &str = "ef680204-9b35-4933-9b10-52ffa9740042";
+const THINGY_NOTIFICATION_TIMEOUT: Duration = Duration::from_secs(3);
+
+// Fills in whatever the standard service didn't provide.
+async fn read_thingy_environment(peripheral: &Peripheral, readings: &mut EnvironmentReadings) {
+    let read = |characteristic_uuid| {
+        read_or_first_notification(
+            peripheral,
+            THINGY_ENVIRONMENT_SERVICE_UUID,
+            characteristic_uuid,
+        )
+    };
+    // Temperature is an int8 plus a uint8 in hundredths, pressure an int32 plus a uint8 in
+    // hundredths of a hPa, humidity a uint8 percentage, gas eCO2 and TVOC as two uint16s.
+    if readings.temperature.is_none()
+        && let Some(value) = read(THINGY_TEMPERATURE_CHARACTERISTIC_UUID).await
+        && let &[integer, decimal, ..] = value.as_slice()
+    {
+        readings.temperature = Some(f32::from(integer as i8) + f32::from(decimal) / 100.0);
+    }
+    if readings.pressure.is_none() {
+        readings.pressure = read(THINGY_PRESSURE_CHARACTERISTIC_UUID)
+            .await
+            .and_then(|value| {
+                let integer = i32::from_le_bytes(value.get(..4)?.try_into().ok()?);
+                Some(integer as f32 + f32::from(*value.get(4)?) / 100.0)
+            });
+    }
+    if readings.humidity.is_none() {
+        readings.humidity = read(THINGY_HUMIDITY_CHARACTERISTIC_UUID)
+            .await
+            .and_then(|value| value.first().map(|humidity| f32::from(*humidity)));
+    }
+    if let Some(value) = read(THINGY_GAS_CHARACTERISTIC_UUID).await
+        && value.len() >= 4
+    {
+        readings.co2 = Some(u16::from_le_bytes([value[0], value[1]]));
+        readings.tvoc = Some(u16::from_le_bytes([value[2], value[3]]));
+    }
+}
+
+// Some sensors (the Thingy's among them) only notify, so fall back to waiting briefly for
+// the first notification when the characteristic isn't readable.
+async fn read_or_first_notification(
+    peripheral: &Peripheral,
+    service_uuid: &str,
+    characteristic_uuid: &str,
+) -> Option<Vec<u8>> {
+    let characteristic = find_characteristic(peripheral, service_uuid, characteristic_uuid)?;
+    if characteristic.properties.contains(CharPropFlags::READ) {
+        return peripheral.read(&characteristic).await.ok();
+    }
+    if !characteristic.properties.contains(CharPropFlags::NOTIFY) {
+        return None;
+    }
+    let mut notifications = peripheral.notifications().await.ok()?;
+    peripheral.subscribe(&characteristic).await.ok()?;
+    let first = async {
+        while let Some(notification) = notifications.next().await {
+            if notification.uuid == characteristic.uuid {
+                return Some(notification.value);
+            }
+        }
+        None
+    };
+    let value = tokio::time::timeout(THINGY_NOTIFICATION_TIMEOUT, first)
+        .await
+        .ok()
+        .flatten();
+    let _ = peripheral.unsubscribe(&characteristic).await;
+    value
+}
+
+// Prefers a write with response so failures are reported, unless the characteristic only
+// accepts writes without one.
+async fn write_characteristic(
+    peripheral: &Peripheral,
+    service_uuid: &str,
+    characteristic_uuid: &str,
+    value: &[u8],
+) -> btleplug::Result<()> {
+    let characteristic = find_characteristic(peripheral, service_uuid, characteristic_uuid)
+        .ok_or(btleplug::Error::NoSuchCharacteristic)?;
+    let write_type = if characteristic.properties.contains(CharPropFlags::WRITE) {
+        WriteType::WithResponse
+    } else if characteristic
+        .properties
+        .contains(CharPropFlags::WRITE_WITHOUT_RESPONSE)
+    {
+        WriteType::WithoutResponse
+    } else {
+        return Err(btleplug::Error::NotSupported(
+            "characteristic is not writable".to_string(),
+        ));
+    };
+    peripheral.write(&characteristic, value, write_type).await
+}
+
+// Runs until the device disconnects, forwarding each measurement as it arrives.
+async fn stream_heart_rate(
+    peripheral: &Peripheral,
+    id: &str,
+    tx: &mpsc::Sender<ScanMessage>,
+) -> btleplug::Result<()> {
+    let Some(characteristic) = find_characteristic(
+        peripheral,
+        HEART_RATE_SERVICE_UUID,
+        HEART_RATE_MEASUREMENT_CHARACTERISTIC_UUID,
+    ) else {
+        return Ok(());
+    };
+    let mut notifications = peripheral.notifications().await?;
+    peripheral.subscribe(&characteristic).await?;
+    while let Some(notification) = notifications.next().await {
+        if notification.uuid != characteristic.uuid {
+            continue;
+        }
+        let Some(bpm) = parse_heart_rate(&notification.value) else {
+            continue;
+        };
+        let msg = ScanMessage::HeartRate {
+            id: id.to_string(),
+            bpm,
+        };
+        if tx.send(msg).is_err() {
+            break;
+        }
+    }
+    Ok(())
+}
+
+async fn disconnect_gatt(peripheral: &Peripheral) {
+    if let Some(characteristic) = find_characteristic(
+        peripheral,
+        HEART_RATE_SERVICE_UUID,
+        HEART_RATE_MEASUREMENT_CHARACTERISTIC_UUID,
+    ) {
+        let _ = peripheral.unsubscribe(&characteristic).await;
+    }
+    let _ = peripheral.disconnect().await;
+}
+
+// Human-readable rendering of a few standard characteristics; None for anything else.
+pub fn decode_characteristic(uuid: &str, value: &[u8]) -> Option<String> {
+    let text = || {
+        let text = String::from_utf8_lossy(value);
+        Some(text.trim_end_matches('\0').to_string())
+    };
+    match short_uuid(uuid)? {
+        0x2a00 | 0x2a24..=0x2a29 => text(),
+        0x2a01 => {
+            let code = u16::from_le_bytes(value.get(..2)?.try_into().ok()?);
+            Some(appearance_name(code).map_or_else(|| format!("0x{code:04X}"), str::to_string))
+        }
+        0x2a19 => Some(format!("{}%", value.first()?)),
+        0x2a37 => Some(format!("{} bpm", parse_heart_rate(value)?)),
+        0x2a6d => {
+            let raw = u32::from_le_bytes(value.get(..4)?.try_into().ok()?);
+            Some(format!("{:.1} hPa", raw as f32 / 1000.0))
+        }
+        0x2a6e => {
+            let raw = i16::from_le_bytes(value.get(..2)?.try_into().ok()?);
+            Some(format!("{:.2} C", f32::from(raw) / 100.0))
+        }
+        0x2a6f => {
+            let raw = u16::from_le_bytes(value.get(..2)?.try_into().ok()?);
+            Some(format!("{:.2}%", f32::from(raw) / 100.0))
+        }
+        _ => None,
+    }
+}
+
+pub fn parse_heart_rate(data: &[u8]) -> Option<u16> {
+    let flags = *data.first()?;
+    if flags & 0x01 == 0 {
+        data.get(1).map(|bpm| u16::from(*bpm))
+    } else {
+        let bytes = data.get(1..3)?;
+        Some(u16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+}
+
+fn find_characteristic(
+    peripheral: &Peripheral,
+    service_uuid: &str,
+    characteristic_uuid: &str,
+) -> Option<Characteristic> {
+    peripheral
+        .characteristics()
+        .into_iter()
+        .find(|characteristic| {
+            characteristic.service_uuid.to_string() == service_uuid
+                && characteristic.uuid.to_string() == characteristic_uuid
+        })
+}
+
+async fn read_device(peripheral: &Peripheral, now: SystemTime) -> DeviceInfo {
+    let id = peripheral.id().to_string();
+    let props = peripheral.properties().await.ok().flatten();
+    let name = props
+        .as_ref()
+        .and_then(|props| props.local_name.clone())
+        .unwrap_or_else(|| "Unknown".to_string());
+    let address = props
+        .as_ref()
+        .map(|props| props.address)
+        .filter(|address| *address != BDAddr::default())
+        .map(|address| address.to_string())
+        .unwrap_or_default();
+    let rssi = props.as_ref().and_then(|props| props.rssi);
+    let connected = peripheral.is_connected().await.unwrap_or(false);
+    let tx_power_level = props.as_ref().and_then(|props| props.tx_power_level);
+    let address_type = props.as_ref().and_then(|props| props.address_type);
+    // btleplug 0.11 doesn't surface the Appearance AD type, the advertising PDU type (and so
+    // whether the device accepts connections) or the raw advertising PDU yet.
+    let appearance = None;
+    let connectable = None;
+    let raw_adv = None;
+    let manufacturer_data = props
+        .as_ref()
+        .map(|props| {
+            props
+                .manufacturer_data
+                .iter()
+                .map(|(key, value)| (*key, value.clone()))
+                .collect::<BTreeMap<_, _>>()
+        })
+        .unwrap_or_default();
+    let service_data = props
+        .as_ref()
+        .map(|props| {
+            props
+                .service_data
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.clone()))
+                .collect::<BTreeMap<_, _>>()
+        })
+        .unwrap_or_default();
+    let services = props
+        .as_ref()
+        .map(|props| props.services.iter().map(|uuid| uuid.to_string()).collect())
+        .unwrap_or_default();
+
+    DeviceInfo {
+        id,
+        address,
+        name,
+        rssi,
+        rssi_smoothed: rssi,
+        connected,
+        tx_power_level,
+        address_type,
+        appearance,
+        connectable,
+        raw_adv,
+        manufacturer_data,
+        service_data,
+        services,
+        adv_count: 1,
+        first_seen: now,
+        last_seen: now,
+    }
+}
+
+struct RuuviDecoder;
+
+#[derive(Default)]
+struct RuuviMeasurement {
+    temperature: Option<f32>,
+    humidity: Option<f32>,
+    pressure: Option<f32>,
+    acceleration: Option<[i16; 3]>,
+    battery_mv: Option<u16>,
+    tx_power: Option<i8>,
+    movement_counter: Option<u8>,
+    sequence: Option<u16>,
+}
+
+impl RuuviDecoder {
+    fn decode(data: &[u8]) -> Option<RuuviMeasurement> {
+        match data.first()? {
+            0x03 => Self::decode_format3(data),
+            0x05 => Self::decode_format5(data),
+            _ => None,
+        }
+    }
+
+    fn decode_format3(data: &[u8]) -> Option<RuuviMeasurement> {
+        if data.len() < 4 || data[0] != 0x03 {
+            return None;
+        }
+
+        let humidity = f32::from(data[1]) * 0.5;
+        let temp_magnitude = f32::from(data[2] & 0x7f) + f32::from(data[3]) / 100.0;
+        let temp = if data[2] & 0x80 != 0 {
+            -temp_magnitude
+        } else {
+            temp_magnitude
+        };
+        let acceleration = match (be_i16(data, 6), be_i16(data, 8), be_i16(data, 10)) {
+            (Some(x), Some(y), Some(z)) => Some([x, y, z]),
+            _ => None,
+        };
+        Some(RuuviMeasurement {
+            temperature: Some(temp),
+            humidity: Some(humidity),
+            pressure: be_u16(data, 4).map(|raw| (f32::from(raw) + 50000.0) / 100.0),
+            acceleration,
+            battery_mv: be_u16(data, 12),
+            ..RuuviMeasurement::default()
+        })
+    }
+
+    fn decode_format5(data: &[u8]) -> Option<RuuviMeasurement> {
+        if data.len() < 5 || data[0] != 0x05 {
+            return None;
+        }
+
+        let temp_raw = i16::from_be_bytes([data[1], data[2]]);
+        let humidity_raw = u16::from_be_bytes([data[3], data[4]]);
+        let temp = if temp_raw == i16::MIN {
+            None
+        } else {
+            Some(f32::from(temp_raw) * 0.005)
+        };
+        let humidity = if humidity_raw == u16::MAX {
+            None
+        } else {
+            Some(f32::from(humidity_raw) * 0.0025)
+        };
+        let pressure = be_u16(data, 5)
+            .filter(|raw| *raw != u16::MAX)
+            .map(|raw| (f32::from(raw) + 50000.0) / 100.0);
+        let acceleration = match (be_i16(data, 7), be_i16(data, 9), be_i16(data, 11)) {
+            (Some(x), Some(y), Some(z)) if x != i16::MIN && y != i16::MIN && z != i16::MIN => {
+                Some([x, y, z])
+            }
+            _ => None,
+        };
+        let power_info = be_u16(data, 13);
+        let battery_mv = power_info
+            .map(|raw| raw >> 5)
+            .filter(|raw| *raw != 0x07ff)
+            .map(|raw| raw + 1600);
+        let tx_power = power_info
+            .map(|raw| (raw & 0x1f) as i8)
+            .filter(|raw| *raw != 0x1f)
+            .map(|raw| raw * 2 - 40);
+        let movement_counter = data.get(15).copied().filter(|raw| *raw != u8::MAX);
+        let sequence = be_u16(data, 16).filter(|raw| *raw != u16::MAX);
+        Some(RuuviMeasurement {
+            temperature: temp,
+            humidity,
+            pressure,
+            acceleration,
+            battery_mv,
+            tx_power,
+            movement_counter,
+            sequence,
+        })
+    }
+}
+
+impl PeripheralDecoder for RuuviDecoder {
+    fn name(&self) -> &str {
+        "Ruuvi"
+    }
+
+    fn category(&self) -> Option<DeviceCategory> {
+        Some(DeviceCategory::Sensor)
+    }
+
+    fn summary(&self, device: &DeviceInfo) -> Option<String> {
+        let data = device.manufacturer_data.get(&0x0499)?;
+        let measurement = Self::decode(data)?;
+        let mut parts = Vec::new();
+        if let Some(temp) = measurement.temperature {
+            parts.push(format!("{temp:.1} C"));
+        }
+        if let Some(humidity) = measurement.humidity {
+            parts.push(format!("{humidity:.1}%"));
+        }
+        if parts.is_empty() {
+            None
+        } else {
+            Some(parts.join(" "))
+        }
+    }
+
+    fn details(&self, device: &DeviceInfo) -> Vec<DetailItem> {
+        let data = match device.manufacturer_data.get(&0x0499) {
+            Some(data) => data,
+            None => return Vec::new(),
+        };
+        let measurement = match Self::decode(data) {
+            Some(measurement) => measurement,
+            None => return Vec::new(),
+        };
+
+        let mut details = Vec::new();
+        if let Some(temp) = measurement.temperature {
+            details.push(DetailItem {
+                label: "Ruuvi temperature".to_string(),
+                value: format!("{temp:.1} C"),
+            });
+        }
+        if let Some(humidity) = measurement.humidity {
+            details.push(DetailItem {
+                label: "Ruuvi humidity".to_string(),
+                value: format!("{humidity:.1}%"),
+            });
+        }
+        if let Some(pressure) = measurement.pressure {
+            details.push(DetailItem {
+                label: "Ruuvi pressure".to_string(),
+                value: format!("{pressure:.2} hPa"),
+            });
+        }
+        if let Some([x, y, z]) = measurement.acceleration {
+            details.push(DetailItem {
+                label: "Ruuvi acceleration".to_string(),
+                value: format!("x {x} mG, y {y} mG, z {z} mG"),
+            });
+        }
+        if let Some(battery_mv) = measurement.battery_mv {
+            details.push(DetailItem {
+                label: "Ruuvi battery".to_string(),
+                value: format!("{battery_mv} mV"),
+            });
+        }
+        if let Some(tx_power) = measurement.tx_power {
+            details.push(DetailItem {
+                label: "Ruuvi tx power".to_string(),
+                value: format!("{tx_power} dBm"),
+            });
+        }
+        if let Some(movement_counter) = measurement.movement_counter {
+            details.push(DetailItem {
+                label: "Ruuvi movement counter".to_string(),
+                value: movement_counter.to_string(),
+            });
+        }
+        if let Some(sequence) = measurement.sequence {
+            details.push(DetailItem {
+                label: "Ruuvi sequence".to_string(),
+                value: sequence.to_string(),
+            });
+        }
+        details
+    }
+}
+
+const EDDYSTONE_SERVICE_UUID: &str = "0000feaa-0000-1000-8000-00805f9b34fb";
+
+struct EddystoneDecoder;
+
+struct EddystoneUid {
+    ranging_data: i8,
+    namespace: String,
+    instance: String,
+}
+
+struct EddystoneTlm {
+    battery_mv: u16,
+    temperature: Option<f32>,
+    adv_count: u32,
+    uptime_secs: f32,
+}
+
+impl EddystoneDecoder {
+    fn frame(device: &DeviceInfo) -> Option<&[u8]> {
+        device
+            .service_data
+            .get(EDDYSTONE_SERVICE_UUID)
+            .map(|data| data.as_slice())
+    }
+
+    fn decode_url(data: &[u8]) -> Option<String> {
+        if data.len() < 3 || data[0] != 0x10 {
+            return None;
+        }
+
+        let scheme = match data[2] {
+            0x00 => "http://www.",
+            0x01 => "https://www.",
+            0x02 => "http://",
+            0x03 => "https://",
+            _ => return None,
+        };
+        let mut url = scheme.to_string();
+        for &byte in &data[3..] {
+            match byte {
+                0x00 => url.push_str(".com/"),
+                0x01 => url.push_str(".org/"),
+                0x02 => url.push_str(".edu/"),
+                0x03 => url.push_str(".net/"),
+                0x04 => url.push_str(".info/"),
+                0x05 => url.push_str(".biz/"),
+                0x06 => url.push_str(".gov/"),
+                0x07 => url.push_str(".com"),
+                0x08 => url.push_str(".org"),
+                0x09 => url.push_str(".edu"),
+                0x0a => url.push_str(".net"),
+                0x0b => url.push_str(".info"),
+                0x0c => url.push_str(".biz"),
+                0x0d => url.push_str(".gov"),
+                0x21..=0x7e => url.push(char::from(byte)),
+                _ => return None,
+            }
+        }
+        Some(url)
+    }
+
+    // The two trailing RFU bytes are optional in practice.
+    fn decode_uid(data: &[u8]) -> Option<EddystoneUid> {
+        if !(18..=20).contains(&data.len()) || data[0] != 0x00 {
+            return None;
+        }
+        let hex = |bytes: &[u8]| bytes.iter().map(|byte| format!("{byte:02x}")).collect();
+        Some(EddystoneUid {
+            ranging_data: data[1] as i8,
+            namespace: hex(&data[2..12]),
+            instance: hex(&data[12..18]),
+        })
+    }
+
+    fn decode_tlm(data: &[u8]) -> Option<EddystoneTlm> {
+        if data.len() < 14 || data[0] != 0x20 || data[1] != 0x00 {
+            return None;
+        }
+
+        let battery_mv = u16::from_be_bytes([data[2], data[3]]);
+        let temp_raw = i16::from_be_bytes([data[4], data[5]]);
+        let temperature = if temp_raw == i16::MIN {
+            None
+        } else {
+            Some(f32::from(temp_raw) / 256.0)
+        };
+        let adv_count = u32::from_be_bytes([data[6], data[7], data[8], data[9]]);
+        let uptime_raw = u32::from_be_bytes([data[10], data[11], data[12], data[13]]);
+        Some(EddystoneTlm {
+            battery_mv,
+            temperature,
+            adv_count,
+            uptime_secs: uptime_raw as f32 / 10.0,
+        })
+    }
+
+    fn tlm_summary(tlm: &EddystoneTlm) -> Option<String> {
+        let mut parts = Vec::new();
+        if tlm.battery_mv != 0 {
+            parts.push(format!("{:.2}V", f32::from(tlm.battery_mv) / 1000.0));
+        }
+        if let Some(temp) = tlm.temperature {
+            parts.push(format!("{temp:.1}C"));
+        }
+        if parts.is_empty() {
+            None
+        } else {
+            Some(parts.join(" "))
+        }
+    }
+}
+
+impl PeripheralDecoder for EddystoneDecoder {
+    fn name(&self) -> &str {
+        "Eddystone"
+    }
+
+    fn category(&self) -> Option<DeviceCategory> {
+        Some(DeviceCategory::Beacon)
+    }
+
+    fn summary(&self, device: &DeviceInfo) -> Option<String> {
+        let data = Self::frame(device)?;
+        Self::decode_url(data)
+            .or_else(|| {
+                let uid = Self::decode_uid(data)?;
+                Some(format!("{}/{}", uid.namespace, uid.instance))
+            })
+            .or_else(|| Self::tlm_summary(&Self::decode_tlm(data)?))
+    }
+
+    fn details(&self, device: &DeviceInfo) -> Vec<DetailItem> {
+        let Some(data) = Self::frame(device) else {
+            return Vec::new();
+        };
+
+        let mut details = Vec::new();
+        if let Some(url) = Self::decode_url(data) {
+            details.push(DetailItem {
+                label: "Eddystone URL".to_string(),
+                value: url,
+            });
+        }
+        if let Some(uid) = Self::decode_uid(data) {
+            details.push(DetailItem {
+                label: "Eddystone namespace".to_string(),
+                value: uid.namespace,
+            });
+            details.push(DetailItem {
+                label: "Eddystone instance".to_string(),
+                value: uid.instance,
+            });
+            details.push(DetailItem {
+                label: "Eddystone ranging data".to_string(),
+                value: format!("{} dBm", uid.ranging_data),
+            });
+        }
+        if let Some(tlm) = Self::decode_tlm(data) {
+            details.push(DetailItem {
+                label: "Eddystone battery".to_string(),
+                value: format!("{} mV", tlm.battery_mv),
+            });
+            if let Some(temp) = tlm.temperature {
+                details.push(DetailItem {
+                    label: "Eddystone temperature".to_string(),
+                    value: format!("{temp:.2} C"),
+                });
+            }
+            details.push(DetailItem {
+                label: "Eddystone adv count".to_string(),
+                value: tlm.adv_count.to_string(),
+            });
+            details.push(DetailItem {
+                label: "Eddystone uptime".to_string(),
+                value: format!("{:.1} s", tlm.uptime_secs),
+            });
+        }
+        details
+    }
+}
+
+const APPLE_COMPANY_ID: u16 = 0x004c;
+
+struct IBeaconDecoder;
+
+struct IBeacon {
+    uuid: Uuid,
+    major: u16,
+    minor: u16,
+    measured_power: i8,
+}
+
+impl IBeaconDecoder {
+    fn decode(data: &[u8]) -> Option<IBeacon> {
+        if data.len() < 23 || data[0] != 0x02 || data[1] != 0x15 {
+            return None;
+        }
+
+        let uuid = Uuid::from_slice(&data[2..18]).ok()?;
+        let major = u16::from_be_bytes([data[18], data[19]]);
+        let minor = u16::from_be_bytes([data[20], data[21]]);
+        let measured_power = i8::from_be_bytes([data[22]]);
+        Some(IBeacon {
+            uuid,
+            major,
+            minor,
+            measured_power,
+        })
+    }
+
+    fn beacon(device: &DeviceInfo) -> Option<IBeacon> {
+        Self::decode(device.manufacturer_data.get(&APPLE_COMPANY_ID)?)
+    }
+}
+
+impl PeripheralDecoder for IBeaconDecoder {
+    fn name(&self) -> &str {
+        "iBeacon"
+    }
+
+    fn category(&self) -> Option<DeviceCategory> {
+        Some(DeviceCategory::Beacon)
+    }
+
+    fn summary(&self, device: &DeviceInfo) -> Option<String> {
+        Some(Self::beacon(device)?.uuid.to_string())
+    }
+
+    fn details(&self, device: &DeviceInfo) -> Vec<DetailItem> {
+        let Some(beacon) = Self::beacon(device) else {
+            return Vec::new();
+        };
+        vec![
+            DetailItem {
+                label: "iBeacon UUID".to_string(),
+                value: beacon.uuid.to_string(),
+            },
+            DetailItem {
+                label: "iBeacon major".to_string(),
+                value: beacon.major.to_string(),
+            },
+            DetailItem {
+                label: "iBeacon minor".to_string(),
+                value: beacon.minor.to_string(),
+            },
+            DetailItem {
+                label: "iBeacon measured power".to_string(),
+                value: format!("{} dBm", beacon.measured_power),
+            },
+        ]
+    }
+}
+
+// Tilt hydrometers are iBeacons whose UUID encodes the color; only the fourth byte varies.
+const TILT_UUID_SUFFIX: [u8; 12] = [
+    0xc5, 0xb1, 0x4b, 0x44, 0xb5, 0x12, 0x13, 0x70, 0xf0, 0x2d, 0x74, 0xde,
+];
+
+struct TiltDecoder;
+
+struct TiltReading {
+    color: &'static str,
+    temperature_f: f32,
+    specific_gravity: f32,
+}
+
+impl TiltDecoder {
+    fn color(uuid: &Uuid) -> Option<&'static str> {
+        let bytes = uuid.as_bytes();
+        if bytes[..3] != [0xa4, 0x95, 0xbb] || bytes[4..] != TILT_UUID_SUFFIX {
+            return None;
+        }
+        match bytes[3] {
+            0x10 => Some("Red"),
+            0x20 => Some("Green"),
+            0x30 => Some("Black"),
+            0x40 => Some("Purple"),
+            0x50 => Some("Orange"),
+            0x60 => Some("Blue"),
+            0x70 => Some("Yellow"),
+            0x80 => Some("Pink"),
+            _ => None,
+        }
+    }
+
+    fn reading(device: &DeviceInfo) -> Option<TiltReading> {
+        let beacon = IBeaconDecoder::beacon(device)?;
+        let color = Self::color(&beacon.uuid)?;
+        // The Tilt Pro reports an extra decimal place in both fields.
+        let (temperature_f, specific_gravity) = if beacon.minor >= 5000 {
+            (
+                f32::from(beacon.major) / 10.0,
+                f32::from(beacon.minor) / 10000.0,
+            )
+        } else {
+            (f32::from(beacon.major), f32::from(beacon.minor) / 1000.0)
+        };
+        Some(TiltReading {
+            color,
+            temperature_f,
+            specific_gravity,
+        })
+    }
+}
+
+impl PeripheralDecoder for TiltDecoder {
+    fn name(&self) -> &str {
+        "Tilt"
+    }
+
+    fn category(&self) -> Option<DeviceCategory> {
+        Some(DeviceCategory::Sensor)
+    }
+
+    fn summary(&self, device: &DeviceInfo) -> Option<String> {
+        let reading = Self::reading(device)?;
+        Some(format!(
+            "{} {}F {:.3}",
+            reading.color, reading.temperature_f, reading.specific_gravity
+        ))
+    }
+
+    fn details(&self, device: &DeviceInfo) -> Vec<DetailItem> {
+        let Some(reading) = Self::reading(device) else {
+            return Vec::new();
+        };
+        vec![
+            DetailItem {
+                label: "Tilt color".to_string(),
+                value: reading.color.to_string(),
+            },
+            DetailItem {
+                label: "Tilt temperature".to_string(),
+                value: format!("{} F", reading.temperature_f),
+            },
+            DetailItem {
+                label: "Tilt specific gravity".to_string(),
+                value: format!("{:.3}", reading.specific_gravity),
+            },
+        ]
+    }
+}
+
+const FIND_MY_MESSAGE_TYPE: u8 = 0x12;
+
+struct FindMyDecoder;
+
+struct FindMyStatus {
+    // Separated trackers broadcast their full rotating key (a 25-byte message); near their
+    // owner they only send the 2-byte short form.
+    separated: bool,
+    kind: &'static str,
+    battery: &'static str,
+}
+
+impl FindMyDecoder {
+    fn decode(data: &[u8]) -> Option<FindMyStatus> {
+        let mut rest = data;
+        while let [message_type, len, tail @ ..] = rest {
+            let value = tail.get(..usize::from(*len))?;
+            if *message_type == FIND_MY_MESSAGE_TYPE {
+                let status = *value.first()?;
+                let separated = match value.len() {
+                    25 => true,
+                    2 => false,
+                    _ => return None,
+                };
+                let kind = match (status >> 4) & 0x03 {
+                    0 => "Apple device",
+                    1 => "AirTag",
+                    2 => "Find My accessory",
+                    _ => "AirPods",
+                };
+                let battery = match status >> 6 {
+                    0 => "full",
+                    1 => "medium",
+                    2 => "low",
+                    _ => "critical",
+                };
+                return Some(FindMyStatus {
+                    separated,
+                    kind,
+                    battery,
+                });
+            }
+            rest = &tail[value.len()..];
+        }
+        None
+    }
+
+    fn status(device: &DeviceInfo) -> Option<FindMyStatus> {
+        Self::decode(device.manufacturer_data.get(&APPLE_COMPANY_ID)?)
+    }
+}
+
+impl PeripheralDecoder for FindMyDecoder {
+    fn name(&self) -> &str {
+        "Find My"
+    }
+
+    fn category(&self) -> Option<DeviceCategory> {
+        Some(DeviceCategory::Tracker)
+    }
+
+    fn summary(&self, device: &DeviceInfo) -> Option<String> {
+        let status = Self::status(device)?;
+        Some(if status.separated {
+            format!("Find My {} separated from owner", status.kind)
+        } else {
+            format!("Find My {} (owner nearby)", status.kind)
+        })
+    }
+
+    fn details(&self, device: &DeviceInfo) -> Vec<DetailItem> {
+        let Some(status) = Self::status(device) else {
+            return Vec::new();
+        };
+        let mode = if status.separated {
+            "separated from owner"
+        } else {
+            "owner nearby"
+        };
+        vec![
+            DetailItem {
+                label: "Find My type".to_string(),
+                value: status.kind.to_string(),
+            },
+            DetailItem {
+                label: "Find My mode".to_string(),
+                value: mode.to_string(),
+            },
+            DetailItem {
+                label: "Find My battery".to_string(),
+                value: status.battery.to_string(),
+            },
+        ]
+    }
+}
+
+struct AppleContinuityDecoder;
+
+impl AppleContinuityDecoder {
+    fn message_name(message_type: u8) -> Option<&'static str> {
+        match message_type {
+            0x03 => Some("AirPrint"),
+            0x05 => Some("AirDrop"),
+            0x06 => Some("HomeKit"),
+            0x07 => Some("AirPods"),
+            0x08 => Some("Hey Siri"),
+            0x09 => Some("AirPlay Target"),
+            0x0a => Some("AirPlay Source"),
+            0x0b => Some("Magic Switch"),
+            0x0c => Some("Handoff"),
+            0x0d => Some("Tethering Target"),
+            0x0e => Some("Tethering Source"),
+            0x0f => Some("Nearby Action"),
+            0x10 => Some("Nearby"),
+            0x12 => Some("Find My"),
+            _ => None,
+        }
+    }
+
+    // Continuity payloads are a sequence of type/length/value messages; the values are
+    // mostly encrypted, so only the types are reported.
+    fn message_types(data: &[u8]) -> Vec<u8> {
+        let mut types = Vec::new();
+        let mut rest = data;
+        while let [message_type, len, tail @ ..] = rest {
+            types.push(*message_type);
+            rest = tail.get(usize::from(*len)..).unwrap_or_default();
+        }
+        types
+    }
+
+    fn messages(device: &DeviceInfo) -> Vec<&'static str> {
+        let Some(data) = device.manufacturer_data.get(&APPLE_COMPANY_ID) else {
+            return Vec::new();
+        };
+        Self::message_types(data)
+            .into_iter()
+            .filter_map(Self::message_name)
+            .collect()
+    }
+}
+
+impl PeripheralDecoder for AppleContinuityDecoder {
+    fn name(&self) -> &str {
+        "Apple Continuity"
+    }
+
+    fn category(&self) -> Option<DeviceCategory> {
+        Some(DeviceCategory::Phone)
+    }
+
+    fn summary(&self, device: &DeviceInfo) -> Option<String> {
+        let messages = Self::messages(device);
+        (!messages.is_empty()).then(|| format!("Apple {}", messages.join(", ")))
+    }
+
+    fn details(&self, device: &DeviceInfo) -> Vec<DetailItem> {
+        Self::messages(device)
+            .into_iter()
+            .map(|name| DetailItem {
+                label: "Apple continuity".to_string(),
+                value: name.to_string(),
+            })
+            .collect()
+    }
+}
+
+struct AltBeaconDecoder;
+
+struct AltBeacon {
+    beacon_id: String,
+    reference_rssi: i8,
+    reserved: u8,
+}
+
+impl AltBeaconDecoder {
+    fn decode(data: &[u8]) -> Option<AltBeacon> {
+        if data.len() < 24 || data[0] != 0xbe || data[1] != 0xac {
+            return None;
+        }
+
+        let beacon_id = data[2..22]
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect::<String>();
+        Some(AltBeacon {
+            beacon_id,
+            reference_rssi: i8::from_be_bytes([data[22]]),
+            reserved: data[23],
+        })
+    }
+
+    fn beacon(device: &DeviceInfo) -> Option<AltBeacon> {
+        device
+            .manufacturer_data
+            .values()
+            .find_map(|data| Self::decode(data))
+    }
+}
+
+impl PeripheralDecoder for AltBeaconDecoder {
+    fn name(&self) -> &str {
+        "AltBeacon"
+    }
+
+    fn category(&self) -> Option<DeviceCategory> {
+        Some(DeviceCategory::Beacon)
+    }
+
+    fn summary(&self, device: &DeviceInfo) -> Option<String> {
+        let beacon = Self::beacon(device)?;
+        Some(format!("AltBeacon {}...", &beacon.beacon_id[..8]))
+    }
+
+    fn details(&self, device: &DeviceInfo) -> Vec<DetailItem> {
+        let Some(beacon) = Self::beacon(device) else {
+            return Vec::new();
+        };
+        vec![
+            DetailItem {
+                label: "AltBeacon ID".to_string(),
+                value: beacon.beacon_id,
+            },
+            DetailItem {
+                label: "AltBeacon reference RSSI".to_string(),
+                value: format!("{} dBm", beacon.reference_rssi),
+            },
+            DetailItem {
+                label: "AltBeacon reserved".to_string(),
+                value: format!("0x{:02x}", beacon.reserved),
+            },
+        ]
+    }
+}
+
+const BTHOME_SERVICE_UUID: &str = "0000fcd2-0000-1000-8000-00805f9b34fb";
+
+struct BtHomeDecoder;
+
+#[derive(Default)]
+struct BtHomeMeasurement {
+    device_info: u8,
+    temperature: Option<f32>,
+    humidity: Option<f32>,
+    battery: Option<u8>,
+    pressure: Option<f32>,
+    illuminance: Option<f32>,
+    buttons: Vec<&'static str>,
+}
+
+impl BtHomeDecoder {
+    fn object_size(id: u8) -> Option<usize> {
+        match id {
+            // Fixed sizes from the BTHome v2 object id table, device info ids (0xf0..) included.
+            0x00 | 0x01 | 0x09 | 0x0f..=0x11 | 0x15..=0x2f | 0x3a | 0x46 | 0x57..=0x59 => Some(1),
+            0x60 => Some(1),
+            0x02 | 0x03 | 0x06..=0x08 | 0x0c..=0x0e | 0x12..=0x14 | 0x3c | 0x3d => Some(2),
+            0x3f..=0x41 | 0x43..=0x45 | 0x47..=0x4a | 0x51 | 0x52 | 0x56 | 0x5a => Some(2),
+            0x5d..=0x5f | 0x61 | 0xf0 => Some(2),
+            0x04 | 0x05 | 0x0a | 0x0b | 0x42 | 0x4b | 0xf2 => Some(3),
+            0x3e | 0x4c..=0x50 | 0x55 | 0x5b | 0x5c | 0xf1 => Some(4),
+            _ => None,
+        }
+    }
+
+    fn button_event(value: u8) -> Option<&'static str> {
+        match value {
+            0x00 => None,
+            0x01 => Some("press"),
+            0x02 => Some("double press"),
+            0x03 => Some("triple press"),
+            0x04 => Some("long press"),
+            0x05 => Some("long double press"),
+            0x06 => Some("long triple press"),
+            0x80 => Some("hold press"),
+            _ => Some("unknown"),
+        }
+    }
+
+    fn decode(data: &[u8]) -> Option<BtHomeMeasurement> {
+        let (&device_info, mut objects) = data.split_first()?;
+        let encrypted = device_info & 0x01 != 0;
+        let version = device_info >> 5;
+        if encrypted || version != 2 {
+            return None;
+        }
+
+        let mut measurement = BtHomeMeasurement {
+            device_info,
+            ..BtHomeMeasurement::default()
+        };
+        while let Some((&id, rest)) = objects.split_first() {
+            let size = match id {
+                // Text and raw objects carry their own length byte.
+                0x53 | 0x54 => 1 + usize::from(*rest.first()?),
+                _ => match Self::object_size(id) {
+                    Some(size) => size,
+                    None => break,
+                },
+            };
+            if rest.len() < size {
+                break;
+            }
+            let (value, rest) = rest.split_at(size);
+            let raw = value
+                .iter()
+                .rev()
+                .fold(0u32, |acc, byte| (acc << 8) | u32::from(*byte));
+            match id {
+                0x01 => measurement.battery = Some(value[0]),
+                0x02 => measurement.temperature = Some(f32::from(raw as u16 as i16) * 0.01),
+                0x03 => measurement.humidity = Some(f32::from(raw as u16) * 0.01),
+                0x04 => measurement.pressure = Some(raw as f32 * 0.01),
+                0x05 => measurement.illuminance = Some(raw as f32 * 0.01),
+                0x2e => measurement.humidity = Some(f32::from(value[0])),
+                0x3a => measurement.buttons.extend(Self::button_event(value[0])),
+                0x45 => measurement.temperature = Some(f32::from(raw as u16 as i16) * 0.1),
+                0x57 => measurement.temperature = Some(f32::from(value[0] as i8)),
+                _ => {}
+            }
+            objects = rest;
+        }
+        Some(measurement)
+    }
+
+    fn measurement(device: &DeviceInfo) -> Option<BtHomeMeasurement> {
+        Self::decode(device.service_data.get(BTHOME_SERVICE_UUID)?)
+    }
+}
+
+impl PeripheralDecoder for BtHomeDecoder {
+    fn name(&self) -> &str {
+        "BTHome"
+    }
+
+    fn category(&self) -> Option<DeviceCategory> {
+        Some(DeviceCategory::Sensor)
+    }
+
+    fn summary(&self, device: &DeviceInfo) -> Option<String> {
+        let measurement = Self::measurement(device)?;
+        let mut parts = Vec::new();
+        if let Some(temp) = measurement.temperature {
+            parts.push(format!("{temp:.1} C"));
+        }
+        if let Some(humidity) = measurement.humidity {
+            parts.push(format!("{humidity:.1}%"));
+        }
+        if parts.is_empty() {
+            None
+        } else {
+            Some(parts.join(" "))
+        }
+    }
+
+    fn details(&self, device: &DeviceInfo) -> Vec<DetailItem> {
+        let Some(measurement) = Self::measurement(device) else {
+            return Vec::new();
+        };
+
+        // Bit 0 is encryption (always clear here), bit 2 trigger-based, bits 5-7 the version.
+        let trigger = if measurement.device_info & 0x04 != 0 {
+            "trigger-based"
+        } else {
+            "regular interval"
+        };
+        let mut details = vec![DetailItem {
+            label: "BTHome device info".to_string(),
+            value: format!(
+                "0b{} (v{}, {trigger})",
+                bit_string(&[measurement.device_info]),
+                measurement.device_info >> 5
+            ),
+        }];
+        if let Some(temp) = measurement.temperature {
+            details.push(DetailItem {
+                label: "BTHome temperature".to_string(),
+                value: format!("{temp:.2} C"),
+            });
+        }
+        if let Some(humidity) = measurement.humidity {
+            details.push(DetailItem {
+                label: "BTHome humidity".to_string(),
+                value: format!("{humidity:.2}%"),
+            });
+        }
+        if let Some(battery) = measurement.battery {
+            details.push(DetailItem {
+                label: "BTHome battery".to_string(),
+                value: format!("{battery}%"),
+            });
+        }
+        if let Some(pressure) = measurement.pressure {
+            details.push(DetailItem {
+                label: "BTHome pressure".to_string(),
+                value: format!("{pressure:.2} hPa"),
+            });
+        }
+        if let Some(illuminance) = measurement.illuminance {
+            details.push(DetailItem {
+                label: "BTHome illuminance".to_string(),
+                value: format!("{illuminance:.2} lx"),
+            });
+        }
+        for button in measurement.buttons {
+            details.push(DetailItem {
+                label: "BTHome button".to_string(),
+                value: button.to_string(),
+            });
+        }
+        details
+    }
+}
+
+const MIBEACON_SERVICE_UUID: &str = "0000fe95-0000-1000-8000-00805f9b34fb";
+
+struct MiBeaconDecoder;
+
+struct MiBeaconObject<'a> {
+    id: u16,
+    data: &'a [u8],
+}
+
+#[derive(Default)]
+struct MiBeaconMeasurement {
+    temperature: Option<f32>,
+    humidity: Option<f32>,
+    battery: Option<u8>,
+}
+
+impl MiBeaconDecoder {
+    fn objects(data: &[u8]) -> Option<Vec<MiBeaconObject<'_>>> {
+        if data.len() < 5 {
+            return None;
+        }
+
+        let frame_control = u16::from_le_bytes([data[0], data[1]]);
+        let encrypted = frame_control & 0x0008 != 0;
+        let has_mac = frame_control & 0x0010 != 0;
+        let has_capability = frame_control & 0x0020 != 0;
+        let has_objects = frame_control & 0x0040 != 0;
+        if encrypted || !has_objects {
+            return None;
+        }
+
+        let mut offset = 5;
+        if has_mac {
+            offset += 6;
+        }
+        if has_capability {
+            let capability = *data.get(offset)?;
+            offset += 1;
+            if capability & 0x20 != 0 {
+                offset += 2;
+            }
+        }
+
+        let mut objects = Vec::new();
+        let mut rest = data.get(offset..)?;
+        while rest.len() >= 3 {
+            let id = u16::from_le_bytes([rest[0], rest[1]]);
+            let len = usize::from(rest[2]);
+            let Some(data) = rest.get(3..3 + len) else {
+                break;
+            };
+            objects.push(MiBeaconObject { id, data });
+            rest = &rest[3 + len..];
+        }
+        Some(objects)
+    }
+
+    fn decode(data: &[u8]) -> Option<MiBeaconMeasurement> {
+        let mut measurement = MiBeaconMeasurement::default();
+        for object in Self::objects(data)? {
+            let value = object.data;
+            match (object.id, value.len()) {
+                (0x1004, 2..) => {
+                    let temp = i16::from_le_bytes([value[0], value[1]]);
+                    measurement.temperature = Some(f32::from(temp) / 10.0);
+                }
+                (0x1006, 2..) => {
+                    let humidity = u16::from_le_bytes([value[0], value[1]]);
+                    measurement.humidity = Some(f32::from(humidity) / 10.0);
+                }
+                (0x100a, 1..) => measurement.battery = Some(value[0]),
+                (0x100d, 4..) => {
+                    let temp = i16::from_le_bytes([value[0], value[1]]);
+                    let humidity = u16::from_le_bytes([value[2], value[3]]);
+                    measurement.temperature = Some(f32::from(temp) / 10.0);
+                    measurement.humidity = Some(f32::from(humidity) / 10.0);
+                }
+                _ => {}
+            }
+        }
+        Some(measurement)
+    }
+
+    fn measurement(device: &DeviceInfo) -> Option<MiBeaconMeasurement> {
+        Self::decode(device.service_data.get(MIBEACON_SERVICE_UUID)?)
+    }
+}
+
+impl PeripheralDecoder for MiBeaconDecoder {
+    fn name(&self) -> &str {
+        "MiBeacon"
+    }
+
+    fn category(&self) -> Option<DeviceCategory> {
+        Some(DeviceCategory::Sensor)
+    }
+
+    fn summary(&self, device: &DeviceInfo) -> Option<String> {
+        let measurement = Self::measurement(device)?;
+        let mut parts = Vec::new();
+        if let Some(temp) = measurement.temperature {
+            parts.push(format!("{temp:.1} C"));
+        }
+        if let Some(humidity) = measurement.humidity {
+            parts.push(format!("{humidity:.0}%"));
+        }
+        if parts.is_empty() {
+            None
+        } else {
+            Some(parts.join(" "))
+        }
+    }
+
+    fn details(&self, device: &DeviceInfo) -> Vec<DetailItem> {
+        let Some(measurement) = Self::measurement(device) else {
+            return Vec::new();
+        };
+
+        let mut details = Vec::new();
+        if let Some(temp) = measurement.temperature {
+            details.push(DetailItem {
+                label: "MiBeacon temperature".to_string(),
+                value: format!("{temp:.1} C"),
+            });
+        }
+        if let Some(humidity) = measurement.humidity {
+            details.push(DetailItem {
+                label: "MiBeacon humidity".to_string(),
+                value: format!("{humidity:.1}%"),
+            });
+        }
+        if let Some(battery) = measurement.battery {
+            details.push(DetailItem {
+                label: "MiBeacon battery".to_string(),
+                value: format!("{battery}%"),
+            });
+        }
+        details
+    }
+}
+
+const MI_FLORA_PRODUCT_ID: u16 = 0x0098;
+
+struct MiFloraDecoder;
+
+enum MiFloraReading {
+    Temperature(f32),
+    Illuminance(u32),
+    Moisture(u8),
+    Fertility(u16),
+}
+
+impl MiFloraDecoder {
+    // The sensor rotates through its readings, so each packet usually carries just one.
+    fn decode(data: &[u8]) -> Option<Vec<MiFloraReading>> {
+        if data.len() < 4 || u16::from_le_bytes([data[2], data[3]]) != MI_FLORA_PRODUCT_ID {
+            return None;
+        }
+        let mut readings = Vec::new();
+        for object in MiBeaconDecoder::objects(data)? {
+            let value = object.data;
+            match (object.id, value.len()) {
+                (0x1004, 2..) => {
+                    let temp = i16::from_le_bytes([value[0], value[1]]);
+                    readings.push(MiFloraReading::Temperature(f32::from(temp) / 10.0));
+                }
+                (0x1007, 3..) => {
+                    let lux = u32::from_le_bytes([value[0], value[1], value[2], 0]);
+                    readings.push(MiFloraReading::Illuminance(lux));
+                }
+                (0x1008, 1..) => readings.push(MiFloraReading::Moisture(value[0])),
+                (0x1009, 2..) => {
+                    let fertility = u16::from_le_bytes([value[0], value[1]]);
+                    readings.push(MiFloraReading::Fertility(fertility));
+                }
+                _ => {}
+            }
+        }
+        Some(readings)
+    }
+
+    fn readings(device: &DeviceInfo) -> Option<Vec<MiFloraReading>> {
+        Self::decode(device.service_data.get(MIBEACON_SERVICE_UUID)?)
+    }
+
+    fn format(reading: &MiFloraReading) -> (&'static str, String) {
+        match reading {
+            MiFloraReading::Temperature(temp) => ("Mi Flora temperature", format!("{temp:.1} C")),
+            MiFloraReading::Illuminance(lux) => ("Mi Flora light", format!("{lux} lx")),
+            MiFloraReading::Moisture(moisture) => ("Mi Flora moisture", format!("{moisture}%")),
+            MiFloraReading::Fertility(fertility) => {
+                ("Mi Flora fertility", format!("{fertility} uS/cm"))
+            }
+        }
+    }
+}
+
+impl PeripheralDecoder for MiFloraDecoder {
+    fn name(&self) -> &str {
+        "Mi Flora"
+    }
+
+    fn category(&self) -> Option<DeviceCategory> {
+        Some(DeviceCategory::Sensor)
+    }
+
+    fn summary(&self, device: &DeviceInfo) -> Option<String> {
+        let readings = Self::readings(device)?;
+        let parts = readings
+            .iter()
+            .map(|reading| Self::format(reading).1)
+            .collect::<Vec<_>>();
+        Some(if parts.is_empty() {
+            "Mi Flora".to_string()
+        } else {
+            format!("Mi Flora {}", parts.join(" "))
+        })
+    }
+
+    fn details(&self, device: &DeviceInfo) -> Vec<DetailItem> {
+        Self::readings(device)
+            .unwrap_or_default()
+            .iter()
+            .map(|reading| {
+                let (label, value) = Self::format(reading);
+                DetailItem {
+                    label: label.to_string(),
+                    value,
+                }
+            })
+            .collect()
+    }
+}
+
+const ENVIRONMENTAL_SENSING_SERVICE_UUID: &str = "0000181a-0000-1000-8000-00805f9b34fb";
+
+struct AtcDecoder;
+
+struct AtcMeasurement {
+    firmware: &'static str,
+    temperature: f32,
+    humidity: f32,
+    battery: u8,
+    battery_mv: u16,
+}
+
+impl AtcDecoder {
+    fn decode(data: &[u8]) -> Option<AtcMeasurement> {
+        match data.len() {
+            13 => Some(AtcMeasurement {
+                firmware: "ATC",
+                temperature: f32::from(i16::from_be_bytes([data[6], data[7]])) / 10.0,
+                humidity: f32::from(data[8]),
+                battery: data[9],
+                battery_mv: u16::from_be_bytes([data[10], data[11]]),
+            }),
+            15 => Some(AtcMeasurement {
+                firmware: "pvvx",
+                temperature: f32::from(i16::from_le_bytes([data[6], data[7]])) / 100.0,
+                humidity: f32::from(u16::from_le_bytes([data[8], data[9]])) / 100.0,
+                battery: data[12],
+                battery_mv: u16::from_le_bytes([data[10], data[11]]),
+            }),
+            _ => None,
+        }
+    }
+
+    fn measurement(device: &DeviceInfo) -> Option<AtcMeasurement> {
+        Self::decode(device.service_data.get(ENVIRONMENTAL_SENSING_SERVICE_UUID)?)
+    }
+}
+
+impl PeripheralDecoder for AtcDecoder {
+    fn name(&self) -> &str {
+        "ATC"
+    }
+
+    fn category(&self) -> Option<DeviceCategory> {
+        Some(DeviceCategory::Sensor)
+    }
+
+    fn summary(&self, device: &DeviceInfo) -> Option<String> {
+        let measurement = Self::measurement(device)?;
+        Some(format!(
+            "{:.1} C {:.0}%",
+            measurement.temperature, measurement.humidity
+        ))
+    }
+
+    fn details(&self, device: &DeviceInfo) -> Vec<DetailItem> {
+        let Some(measurement) = Self::measurement(device) else {
+            return Vec::new();
+        };
+        vec![
+            DetailItem {
+                label: "Firmware".to_string(),
+                value: measurement.firmware.to_string(),
+            },
+            DetailItem {
+                label: format!("{} temperature", measurement.firmware),
+                value: format!("{:.2} C", measurement.temperature),
+            },
+            DetailItem {
+                label: format!("{} humidity", measurement.firmware),
+                value: format!("{:.2}%", measurement.humidity),
+            },
+            DetailItem {
+                label: format!("{} battery", measurement.firmware),
+                value: format!("{}%", measurement.battery),
+            },
+            DetailItem {
+                label: format!("{} battery voltage", measurement.firmware),
+                value: format!("{} mV", measurement.battery_mv),
+            },
+        ]
+    }
+}
+
+const GOVEE_COMPANY_ID: u16 = 0xec88;
+
+struct GoveeDecoder;
+
+// The H5179 advertises under its own company id with little-endian hundredths instead of
+// the H5075's packed decimal, so it gets a separate decoder keyed on both id and length.
+const GOVEE_H5179_COMPANY_ID: u16 = 0x8801;
+
+struct GoveeH5179Decoder;
+
+struct GoveeMeasurement {
+    temperature: f32,
+    humidity: f32,
+    battery: u8,
+}
+
+impl GoveeMeasurement {
+    fn summary(&self) -> String {
+        format!("{:.1} C {:.1}%", self.temperature, self.humidity)
+    }
+
+    fn details(&self) -> Vec<DetailItem> {
+        vec![
+            DetailItem {
+                label: "Govee temperature".to_string(),
+                value: format!("{:.1} C", self.temperature),
+            },
+            DetailItem {
+                label: "Govee humidity".to_string(),
+                value: format!("{:.1}%", self.humidity),
+            },
+            DetailItem {
+                label: "Govee battery".to_string(),
+                value: format!("{}%", self.battery),
+            },
+        ]
+    }
+}
+
+impl GoveeDecoder {
+    fn decode_h5075(data: &[u8]) -> Option<GoveeMeasurement> {
+        if data.len() != 6 {
+            return None;
+        }
+
+        let packed = u32::from_be_bytes([0, data[1], data[2], data[3]]);
+        let negative = packed & 0x80_0000 != 0;
+        let packed = packed & 0x7f_ffff;
+        let temp = (packed / 1000) as f32 / 10.0;
+        let humidity = (packed % 1000) as f32 / 10.0;
+        Some(GoveeMeasurement {
+            temperature: if negative { -temp } else { temp },
+            humidity,
+            battery: data[4],
+        })
+    }
+
+    fn measurement(device: &DeviceInfo) -> Option<GoveeMeasurement> {
+        Self::decode_h5075(device.manufacturer_data.get(&GOVEE_COMPANY_ID)?)
+    }
+}
+
+impl PeripheralDecoder for GoveeDecoder {
+    fn name(&self) -> &str {
+        "Govee"
+    }
+
+    fn category(&self) -> Option<DeviceCategory> {
+        Some(DeviceCategory::Sensor)
+    }
+
+    fn summary(&self, device: &DeviceInfo) -> Option<String> {
+        Some(Self::measurement(device)?.summary())
+    }
+
+    fn details(&self, device: &DeviceInfo) -> Vec<DetailItem> {
+        Self::measurement(device)
+            .map(|measurement| measurement.details())
+            .unwrap_or_default()
+    }
+}
+
+impl GoveeH5179Decoder {
+    fn decode(data: &[u8]) -> Option<GoveeMeasurement> {
+        // 01 00 01 01, then temperature (i16) and humidity (u16) in hundredths, then battery.
+        if data.len() != 9 {
+            return None;
+        }
+
+        let temperature = i16::from_le_bytes([data[4], data[5]]);
+        let humidity = u16::from_le_bytes([data[6], data[7]]);
+        Some(GoveeMeasurement {
+            temperature: f32::from(temperature) / 100.0,
+            humidity: f32::from(humidity) / 100.0,
+            battery: data[8],
+        })
+    }
+
+    fn measurement(device: &DeviceInfo) -> Option<GoveeMeasurement> {
+        Self::decode(device.manufacturer_data.get(&GOVEE_H5179_COMPANY_ID)?)
+    }
+}
+
+impl PeripheralDecoder for GoveeH5179Decoder {
+    fn name(&self) -> &str {
+        "Govee H5179"
+    }
+
+    fn category(&self) -> Option<DeviceCategory> {
+        Some(DeviceCategory::Sensor)
+    }
+
+    fn summary(&self, device: &DeviceInfo) -> Option<String> {
+        Some(Self::measurement(device)?.summary())
+    }
+
+    fn details(&self, device: &DeviceInfo) -> Vec<DetailItem> {
+        Self::measurement(device)
+            .map(|measurement| measurement.details())
+            .unwrap_or_default()
+    }
+}
+
+// Oral-B brushes advertise their live state under Procter & Gamble's company id: protocol
+// and model bytes, then state, pressure flags, elapsed time (minutes, seconds), mode and
+// the current mouth sector.
+const ORALB_COMPANY_ID: u16 = 0x00dc;
+// Most Oral-B timers are set to the dentist-recommended two minutes.
+const ORALB_BRUSHING_TARGET: u16 = 120;
+
+struct OralBDecoder;
+
+struct OralBState {
+    state: u8,
+    high_pressure: bool,
+    elapsed: u16,
+    mode: u8,
+    sector: u8,
+}
+
+impl OralBState {
+    fn state_name(&self) -> String {
+        match self.state {
+            1 => "initializing".to_string(),
+            2 => "idle".to_string(),
+            3 => "running".to_string(),
+            4 => "charging".to_string(),
+            5 => "setup".to_string(),
+            6 => "flight menu".to_string(),
+            8 => "selection menu".to_string(),
+            9 => "off".to_string(),
+            115 => "sleeping".to_string(),
+            116 => "transport".to_string(),
+            other => format!("unknown ({other})"),
+        }
+    }
+
+    fn mode_name(&self) -> String {
+        match self.mode {
+            0 => "off".to_string(),
+            1 => "daily clean".to_string(),
+            2 => "sensitive".to_string(),
+            3 => "massage".to_string(),
+            4 => "whitening".to_string(),
+            5 => "deep clean".to_string(),
+            6 => "tongue cleaning".to_string(),
+            7 => "turbo".to_string(),
+            other => format!("unknown ({other})"),
+        }
+    }
+
+    fn remaining(&self) -> u16 {
+        ORALB_BRUSHING_TARGET.saturating_sub(self.elapsed)
+    }
+}
+
+impl OralBDecoder {
+    fn decode(data: &[u8]) -> Option<OralBState> {
+        // Older brushes send 9 bytes, newer ones 11.
+        if !matches!(data.len(), 9 | 11) {
+            return None;
+        }
+
+        Some(OralBState {
+            state: data[3],
+            high_pressure: data[4] & 0x80 != 0,
+            elapsed: u16::from(data[5]) * 60 + u16::from(data[6]),
+            mode: data[7],
+            sector: data[8],
+        })
+    }
+
+    fn state(device: &DeviceInfo) -> Option<OralBState> {
+        Self::decode(device.manufacturer_data.get(&ORALB_COMPANY_ID)?)
+    }
+}
+
+impl PeripheralDecoder for OralBDecoder {
+    fn name(&self) -> &str {
+        "Oral-B"
+    }
+
+    fn summary(&self, device: &DeviceInfo) -> Option<String> {
+        let state = Self::state(device)?;
+        if state.state != 3 {
+            return Some(format!("Oral-B {}", state.state_name()));
+        }
+        let remaining = state.remaining();
+        Some(format!(
+            "Oral-B brushing, {}:{:02} left",
+            remaining / 60,
+            remaining % 60
+        ))
+    }
+
+    fn details(&self, device: &DeviceInfo) -> Vec<DetailItem> {
+        let Some(state) = Self::state(device) else {
+            return Vec::new();
+        };
+        let sector = match state.sector {
+            254 => "last".to_string(),
+            255 => "none".to_string(),
+            sector => sector.to_string(),
+        };
+        let pressure = if state.high_pressure {
+            "high"
+        } else {
+            "normal"
+        };
+        let remaining = state.remaining();
+        vec![
+            DetailItem {
+                label: "Oral-B state".to_string(),
+                value: state.state_name(),
+            },
+            DetailItem {
+                label: "Oral-B mode".to_string(),
+                value: state.mode_name(),
+            },
+            DetailItem {
+                label: "Oral-B brushing time".to_string(),
+                value: format!("{}:{:02}", state.elapsed / 60, state.elapsed % 60),
+            },
+            DetailItem {
+                label: "Oral-B remaining".to_string(),
+                value: format!("{}:{:02}", remaining / 60, remaining % 60),
+            },
+            DetailItem {
+                label: "Oral-B pressure".to_string(),
+                value: pressure.to_string(),
+            },
+            DetailItem {
+                label: "Oral-B sector".to_string(),
+                value: sector,
+            },
+        ]
+    }
+}
+
+const MICROSOFT_COMPANY_ID: u16 = 0x0006;
+const SWIFT_PAIR_BEACON_ID: u8 = 0x03;
+
+struct SwiftPairDecoder;
+
+struct SwiftPair {
+    scenario: &'static str,
+    display_name: Option<String>,
+}
+
+impl SwiftPairDecoder {
+    fn decode(data: &[u8]) -> Option<SwiftPair> {
+        let [SWIFT_PAIR_BEACON_ID, scenario, _reserved_rssi, rest @ ..] = data else {
+            return None;
+        };
+        // The BR/EDR scenarios carry an address and/or a 3-byte icon before the name.
+        let (scenario, name_offset) = match scenario {
+            0x00 => ("LE", 0),
+            0x01 => ("BR/EDR via LE", 9),
+            0x02 => ("LE and BR/EDR", 3),
+            _ => return None,
+        };
+        let display_name = rest
+            .get(name_offset..)
+            .filter(|name| !name.is_empty())
+            .map(|name| {
+                String::from_utf8_lossy(name)
+                    .trim_end_matches('\0')
+                    .to_string()
+            });
+        Some(SwiftPair {
+            scenario,
+            display_name,
+        })
+    }
+
+    fn advertisement(device: &DeviceInfo) -> Option<SwiftPair> {
+        Self::decode(device.manufacturer_data.get(&MICROSOFT_COMPANY_ID)?)
+    }
+}
+
+impl PeripheralDecoder for SwiftPairDecoder {
+    fn name(&self) -> &str {
+        "Swift Pair"
+    }
+
+    fn category(&self) -> Option<DeviceCategory> {
+        Some(DeviceCategory::Input)
+    }
+
+    fn summary(&self, device: &DeviceInfo) -> Option<String> {
+        let advertisement = Self::advertisement(device)?;
+        Some(match advertisement.display_name {
+            Some(name) => format!("Swift Pair \"{name}\""),
+            None => "Swift Pair".to_string(),
+        })
+    }
+
+    fn details(&self, device: &DeviceInfo) -> Vec<DetailItem> {
+        let Some(advertisement) = Self::advertisement(device) else {
+            return Vec::new();
+        };
+        let mut items = vec![DetailItem {
+            label: "Swift Pair scenario".to_string(),
+            value: advertisement.scenario.to_string(),
+        }];
+        if let Some(name) = advertisement.display_name {
+            items.push(DetailItem {
+                label: "Swift Pair name".to_string(),
+                value: name,
+            });
+        }
+        items
+    }
+}
+
+// IBS-TH1/TH2 sensors advertise as "sps" (or "tps" for the probe variant) and put the
+// temperature where the company id normally goes.
+const INKBIRD_NAMES: [&str; 2] = ["sps", "tps"];
+
+struct InkbirdDecoder;
+
+struct InkbirdMeasurement {
+    temperature: f32,
+    humidity: f32,
+    battery: u8,
+}
+
+impl InkbirdDecoder {
+    fn decode(temperature: u16, data: &[u8]) -> Option<InkbirdMeasurement> {
+        if data.len() != 7 {
+            return None;
+        }
+        Some(InkbirdMeasurement {
+            temperature: f32::from(temperature as i16) / 100.0,
+            humidity: f32::from(u16::from_le_bytes([data[0], data[1]])) / 100.0,
+            battery: data[5],
+        })
+    }
+
+    fn measurement(device: &DeviceInfo) -> Option<InkbirdMeasurement> {
+        if !INKBIRD_NAMES.contains(&device.name.as_str()) {
+            return None;
+        }
+        let (temperature, data) = device.manufacturer_data.iter().next()?;
+        Self::decode(*temperature, data)
+    }
+}
+
+impl PeripheralDecoder for InkbirdDecoder {
+    fn name(&self) -> &str {
+        "Inkbird"
+    }
+
+    fn category(&self) -> Option<DeviceCategory> {
+        Some(DeviceCategory::Sensor)
+    }
+
+    fn summary(&self, device: &DeviceInfo) -> Option<String> {
+        let measurement = Self::measurement(device)?;
+        Some(format!(
+            "{:.1} C {:.1}%",
+            measurement.temperature, measurement.humidity
+        ))
+    }
+
+    fn details(&self, device: &DeviceInfo) -> Vec<DetailItem> {
+        let Some(measurement) = Self::measurement(device) else {
+            return Vec::new();
+        };
+        vec![
+            DetailItem {
+                label: "Inkbird temperature".to_string(),
+                value: format!("{:.2} C", measurement.temperature),
+            },
+            DetailItem {
+                label: "Inkbird humidity".to_string(),
+                value: format!("{:.1}%", measurement.humidity),
+            },
+            DetailItem {
+                label: "Inkbird battery".to_string(),
+                value: format!("{}%", measurement.battery),
+            },
+        ]
+    }
+}
+
+// (min, max, step) for each value packed into a SensorPush advertisement, keyed by device type.
+const SENSORPUSH_HTW: &[(f64, f64, f64)] = &[(-40.0, 125.0, 0.0025), (0.0, 100.0, 0.0025)];
+const SENSORPUSH_HTP_XW: &[(f64, f64, f64)] = &[
+    (-40.0, 140.0, 0.0025),
+    (0.0, 100.0, 0.0025),
+    (30000.0, 125000.0, 1.0),
+];
+
+struct SensorPushDecoder;
+
+struct SensorPushMeasurement {
+    model: &'static str,
+    temperature: f64,
+    humidity: f64,
+    pressure: Option<f64>,
+}
+
+impl SensorPushDecoder {
+    // The company id slot carries payload, so `data` is the whole manufacturer data
+    // including those two bytes.
+    fn decode(data: &[u8]) -> Option<SensorPushMeasurement> {
+        let header = *data.first()?;
+        if header & 0x03 != 0 {
+            return None;
+        }
+        let (model, params, len) = match 64 + (header >> 2) {
+            64 => ("HTP.xw", SENSORPUSH_HTP_XW, 7),
+            65 => ("HT.w", SENSORPUSH_HTW, 5),
+            _ => return None,
+        };
+        if data.len() != len {
+            return None;
+        }
+        let packed = data[1..]
+            .iter()
+            .rev()
+            .fold(0u64, |acc, byte| (acc << 8) | u64::from(*byte));
+
+        // Values are mixed-radix digits, each with as many steps as its range allows.
+        let mut remaining = packed;
+        let mut values = params.iter().map(|(min, max, step)| {
+            let steps = ((max - min) / step + step / 2.0) as u64 + 1;
+            let count = remaining % steps;
+            remaining /= steps;
+            count as f64 * step + min
+        });
+        Some(SensorPushMeasurement {
+            model,
+            temperature: values.next()?,
+            humidity: values.next()?,
+            pressure: values.next(),
+        })
+    }
+
+    fn measurement(device: &DeviceInfo) -> Option<SensorPushMeasurement> {
+        if !device.name.starts_with("SensorPush") || device.manufacturer_data.len() != 1 {
+            return None;
+        }
+        let (company_id, payload) = device.manufacturer_data.iter().next()?;
+        let mut data = company_id.to_le_bytes().to_vec();
+        data.extend_from_slice(payload);
+        Self::decode(&data)
+    }
+}
+
+impl PeripheralDecoder for SensorPushDecoder {
+    fn name(&self) -> &str {
+        "SensorPush"
+    }
+
+    fn category(&self) -> Option<DeviceCategory> {
+        Some(DeviceCategory::Sensor)
+    }
+
+    fn summary(&self, device: &DeviceInfo) -> Option<String> {
+        let measurement = Self::measurement(device)?;
+        let mut summary = format!(
+            "SensorPush {:.1} C {:.1}%",
+            measurement.temperature, measurement.humidity
+        );
+        if let Some(pressure) = measurement.pressure {
+            summary.push_str(&format!(" {:.1} hPa", pressure / 100.0));
+        }
+        Some(summary)
+    }
+
+    fn details(&self, device: &DeviceInfo) -> Vec<DetailItem> {
+        let Some(measurement) = Self::measurement(device) else {
+            return Vec::new();
+        };
+        let mut items = vec![
+            DetailItem {
+                label: "SensorPush model".to_string(),
+                value: measurement.model.to_string(),
+            },
+            DetailItem {
+                label: "SensorPush temperature".to_string(),
+                value: format!("{:.2} C", measurement.temperature),
+            },
+            DetailItem {
+                label: "SensorPush humidity".to_string(),
+                value: format!("{:.2}%", measurement.humidity),
+            },
+        ];
+        if let Some(pressure) = measurement.pressure {
+            items.push(DetailItem {
+                label: "SensorPush pressure".to_string(),
+                value: format!("{:.2} hPa", pressure / 100.0),
+            });
+        }
+        items
+    }
+}
+
+// Older firmware uses 0x0d00, newer firmware the assigned 0xfd3d.
+const SWITCHBOT_SERVICE_UUIDS: [&str; 2] = [
+    "00000d00-0000-1000-8000-00805f9b34fb",
+    "0000fd3d-0000-1000-8000-00805f9b34fb",
+];
+
+struct SwitchBotDecoder;
+
+enum SwitchBotState {
+    Meter { temperature: f32, humidity: u8 },
+    Contact { open: bool },
+    Bot { switch_mode: bool, on: bool },
+}
+
+struct SwitchBotReading {
+    state: SwitchBotState,
+    battery: u8,
+}
+
+impl SwitchBotDecoder {
+    fn decode(data: &[u8]) -> Option<SwitchBotReading> {
+        let device_type = *data.first()? & 0x7f;
+        let battery = data.get(2)? & 0x7f;
+        let state = match device_type {
+            // 'T' Meter, 'i' Meter Plus, 'w' Outdoor Meter.
+            b'T' | b'i' | b'w' if data.len() >= 6 => {
+                let magnitude = f32::from(data[4] & 0x7f) + f32::from(data[3] & 0x0f) / 10.0;
+                let temperature = if data[4] & 0x80 != 0 {
+                    magnitude
+                } else {
+                    -magnitude
+                };
+                SwitchBotState::Meter {
+                    temperature,
+                    humidity: data[5] & 0x7f,
+                }
+            }
+            b'd' if data.len() >= 4 => SwitchBotState::Contact {
+                open: (data[3] >> 1) & 0x03 != 0,
+            },
+            // In switch mode bit 6 is set while off; in press mode the Bot is never "on".
+            b'H' => {
+                let switch_mode = data[1] & 0x80 != 0;
+                SwitchBotState::Bot {
+                    switch_mode,
+                    on: switch_mode && data[1] & 0x40 == 0,
+                }
+            }
+            _ => return None,
+        };
+        Some(SwitchBotReading { state, battery })
+    }
+
+    fn reading(device: &DeviceInfo) -> Option<SwitchBotReading> {
+        SWITCHBOT_SERVICE_UUIDS
+            .iter()
+            .find_map(|uuid| device.service_data.get(*uuid))
+            .and_then(|data| Self::decode(data))
+    }
+}
+
+impl PeripheralDecoder for SwitchBotDecoder {
+    fn name(&self) -> &str {
+        "SwitchBot"
+    }
+
+    fn category(&self) -> Option<DeviceCategory> {
+        Some(DeviceCategory::Sensor)
+    }
+
+    fn summary(&self, device: &DeviceInfo) -> Option<String> {
+        let reading = Self::reading(device)?;
+        Some(match reading.state {
+            SwitchBotState::Meter {
+                temperature,
+                humidity,
+            } => format!("{temperature:.1} C {humidity}%"),
+            SwitchBotState::Contact { open } => if open { "Open" } else { "Closed" }.to_string(),
+            SwitchBotState::Bot { on, .. } => if on { "Bot on" } else { "Bot off" }.to_string(),
+        })
+    }
+
+    fn details(&self, device: &DeviceInfo) -> Vec<DetailItem> {
+        let Some(reading) = Self::reading(device) else {
+            return Vec::new();
+        };
+        let mut items = match reading.state {
+            SwitchBotState::Meter {
+                temperature,
+                humidity,
+            } => vec![
+                DetailItem {
+                    label: "SwitchBot temperature".to_string(),
+                    value: format!("{temperature:.1} C"),
+                },
+                DetailItem {
+                    label: "SwitchBot humidity".to_string(),
+                    value: format!("{humidity}%"),
+                },
+            ],
+            SwitchBotState::Contact { open } => vec![DetailItem {
+                label: "SwitchBot contact".to_string(),
+                value: if open { "open" } else { "closed" }.to_string(),
+            }],
+            SwitchBotState::Bot { switch_mode, on } => vec![
+                DetailItem {
+                    label: "SwitchBot bot".to_string(),
+                    value: if on { "on" } else { "off" }.to_string(),
+                },
+                DetailItem {
+                    label: "SwitchBot mode".to_string(),
+                    value: if switch_mode { "switch" } else { "press" }.to_string(),
+                },
+            ],
+        };
+        items.push(DetailItem {
+            label: "SwitchBot battery".to_string(),
+            value: format!("{}%", reading.battery),
+        });
+        items
+    }
+}
+
+struct HeartRateDecoder;
+
+struct HeartRateStrap<'a> {
+    // Polar names its straps "Polar <model> <device id>", e.g. "Polar H10 1A2B3C4D".
+    polar: Option<(&'a str, &'a str)>,
+}
+
+impl HeartRateDecoder {
+    fn strap(device: &DeviceInfo) -> Option<HeartRateStrap<'_>> {
+        let advertised = device
+            .services
+            .iter()
+            .any(|uuid| uuid.eq_ignore_ascii_case(HEART_RATE_SERVICE_UUID));
+        if !advertised {
+            return None;
+        }
+        let polar = device
+            .name
+            .strip_prefix("Polar ")
+            .and_then(|rest| rest.rsplit_once(' '))
+            .filter(|(_, id)| id.len() == 8 && id.chars().all(|c| c.is_ascii_hexdigit()));
+        Some(HeartRateStrap { polar })
+    }
+}
+
+impl PeripheralDecoder for HeartRateDecoder {
+    fn name(&self) -> &str {
+        "Heart Rate"
+    }
+
+    fn category(&self) -> Option<DeviceCategory> {
+        Some(DeviceCategory::HeartRate)
+    }
+
+    fn summary(&self, device: &DeviceInfo) -> Option<String> {
+        let strap = Self::strap(device)?;
+        Some(match strap.polar {
+            Some((model, id)) => format!("Polar {model} HR strap {id}"),
+            None => "Heart rate sensor".to_string(),
+        })
+    }
+
+    fn details(&self, device: &DeviceInfo) -> Vec<DetailItem> {
+        let Some(strap) = Self::strap(device) else {
+            return Vec::new();
+        };
+        let mut items = vec![DetailItem {
+            label: "Heart rate service".to_string(),
+            value: "advertised".to_string(),
+        }];
+        if let Some((model, id)) = strap.polar {
+            items.push(DetailItem {
+                label: "Polar model".to_string(),
+                value: model.to_string(),
+            });
+            items.push(DetailItem {
+                label: "Polar device ID".to_string(),
+                value: id.to_string(),
+            });
+        }
+        items
+    }
+}
+
+const NORDIC_UART_SERVICE_UUID: &str = "6e400001-b5a3-f393-e0a9-e50e24dcca9e";
+
+// NUS traffic only flows over a connection, so all we can do passively is spot the service.
+struct NordicUartDecoder;
+
+impl NordicUartDecoder {
+    fn advertised(device: &DeviceInfo) -> bool {
+        device
+            .services
+            .iter()
+            .any(|uuid| uuid.eq_ignore_ascii_case(NORDIC_UART_SERVICE_UUID))
+    }
+}
+
+impl PeripheralDecoder for NordicUartDecoder {
+    fn name(&self) -> &str {
+        "Nordic UART"
+    }
+
+    fn summary(&self, device: &DeviceInfo) -> Option<String> {
+        Self::advertised(device).then(|| "Nordic UART".to_string())
+    }
+
+    fn details(&self, device: &DeviceInfo) -> Vec<DetailItem> {
+        if !Self::advertised(device) {
+            return Vec::new();
+        }
+        vec![DetailItem {
+            label: "Nordic UART".to_string(),
+            value: "service advertised".to_string(),
+        }]
+    }
+}
+
+const THINGY_CONFIGURATION_SERVICE_UUID: &str = "ef680100-9b35-4933-9b10-52ffa9740042";
+
+// The Thingy:52 advertises its configuration service; environment readings are only
+// available over a connection (see read_thingy_environment).
+struct ThingyDecoder;
+
+impl ThingyDecoder {
+    fn advertised(device: &DeviceInfo) -> bool {
+        device
+            .services
+            .iter()
+            .any(|uuid| uuid.eq_ignore_ascii_case(THINGY_CONFIGURATION_SERVICE_UUID))
+    }
+}
+
+impl PeripheralDecoder for ThingyDecoder {
+    fn name(&self) -> &str {
+        "Thingy:52"
+    }
+
+    fn category(&self) -> Option<DeviceCategory> {
+        Some(DeviceCategory::Sensor)
+    }
+
+    fn summary(&self, device: &DeviceInfo) -> Option<String> {
+        Self::advertised(device).then(|| "Thingy:52".to_string())
+    }
+
+    fn details(&self, device: &DeviceInfo) -> Vec<DetailItem> {
+        if !Self::advertised(device) {
+            return Vec::new();
+        }
+        vec![DetailItem {
+            label: "Thingy:52".to_string(),
+            value: "connect to read environment".to_string(),
+        }]
+    }
+}
+
+// The CC2650 advertises the movement service as a 16-bit UUID; its GATT services live
+// under TI's own base UUID.
+const SENSORTAG_SERVICE_UUIDS: [(&str, &str); 6] = [
+    ("0000aa80-0000-1000-8000-00805f9b34fb", "Movement"),
+    ("f000aa00-0451-4000-b000-000000000000", "IR temperature"),
+    ("f000aa20-0451-4000-b000-000000000000", "Humidity"),
+    ("f000aa40-0451-4000-b000-000000000000", "Barometer"),
+    ("f000aa70-0451-4000-b000-000000000000", "Optical"),
+    ("f000aa80-0451-4000-b000-000000000000", "Movement"),
+];
+
+// Readings need a connection and a write to each sensor's config characteristic, so only
+// the advertisement is recognized here.
+struct SensorTagDecoder;
+
+impl SensorTagDecoder {
+    fn services(device: &DeviceInfo) -> Vec<&'static str> {
+        let mut names = Vec::new();
+        for (uuid, name) in SENSORTAG_SERVICE_UUIDS {
+            if device
+                .services
+                .iter()
+                .any(|service| service.eq_ignore_ascii_case(uuid))
+                && !names.contains(&name)
+            {
+                names.push(name);
+            }
+        }
+        names
+    }
+
+    fn recognized(device: &DeviceInfo) -> bool {
+        device.name.contains("SensorTag") || !Self::services(device).is_empty()
+    }
+}
+
+impl PeripheralDecoder for SensorTagDecoder {
+    fn name(&self) -> &str {
+        "SensorTag"
+    }
+
+    fn category(&self) -> Option<DeviceCategory> {
+        Some(DeviceCategory::Sensor)
+    }
+
+    fn summary(&self, device: &DeviceInfo) -> Option<String> {
+        Self::recognized(device).then(|| "TI SensorTag".to_string())
+    }
+
+    fn details(&self, device: &DeviceInfo) -> Vec<DetailItem> {
+        if !Self::recognized(device) {
+            return Vec::new();
+        }
+        let services = Self::services(device);
+        vec![DetailItem {
+            label: "TI SensorTag".to_string(),
+            value: if services.is_empty() {
+                "recognized by name".to_string()
+            } else {
+                services.join(", ")
+            },
+        }]
+    }
+}
+
+const VICTRON_COMPANY_ID: u16 = 0x02e1;
+const VICTRON_PRODUCT_ADVERTISEMENT: u8 = 0x10;
+const VICTRON_SOLAR_CHARGER: u8 = 0x01;
+const VICTRON_BATTERY_MONITOR: u8 = 0x02;
+
+#[derive(Default)]
+pub struct VictronDecoder {
+    pub keys: HashMap<String, [u8; 16]>,
+}
+
+struct VictronReadout {
+    voltage: f32,
+    current: f32,
+    state_of_charge: Option<f32>,
+}
+
+impl VictronDecoder {
+    fn payload(device: &DeviceInfo) -> Option<&[u8]> {
+        let data = device.manufacturer_data.get(&VICTRON_COMPANY_ID)?;
+        (data.len() > 8 && data[0] == VICTRON_PRODUCT_ADVERTISEMENT).then_some(data.as_slice())
+    }
+
+    fn decrypt(data: &[u8], key: &[u8; 16]) -> Option<Vec<u8>> {
+        use ctr::cipher::{KeyIvInit as _, StreamCipher as _};
+
+        // Byte 7 echoes the first key byte so a wrong key can be spotted without decrypting.
+        if data[7] != key[0] {
+            return None;
+        }
+        let mut iv = [0u8; 16];
+        iv[..2].copy_from_slice(&data[5..7]);
+        let mut payload = data[8..].to_vec();
+        let mut cipher = ctr::Ctr128LE::<aes::Aes128>::new(key.into(), &iv.into());
+        cipher.apply_keystream(&mut payload);
+        Some(payload)
+    }
+
+    fn decode(record_type: u8, payload: &[u8]) -> Option<VictronReadout> {
+        // Records are little-endian bit fields, so read them as one wide integer.
+        let mut bytes = [0u8; 16];
+        let len = payload.len().min(16);
+        bytes[..len].copy_from_slice(&payload[..len]);
+        let bits = u128::from_le_bytes(bytes);
+        let field = |offset: u32, width: u32| (bits >> offset) & ((1u128 << width) - 1);
+        let signed = |offset: u32, width: u32| {
+            let value = field(offset, width) as i64;
+            if value & (1 << (width - 1)) != 0 {
+                value - (1 << width)
+            } else {
+                value
+            }
+        };
+
+        match record_type {
+            VICTRON_SOLAR_CHARGER if payload.len() >= 6 => Some(VictronReadout {
+                voltage: signed(16, 16) as f32 / 100.0,
+                current: signed(32, 16) as f32 / 10.0,
+                state_of_charge: None,
+            }),
+            VICTRON_BATTERY_MONITOR if payload.len() >= 15 => Some(VictronReadout {
+                voltage: signed(16, 16) as f32 / 100.0,
+                current: signed(66, 22) as f32 / 1000.0,
+                state_of_charge: Some(field(108, 10) as f32 / 10.0),
+            }),
+            _ => None,
+        }
+    }
+
+    fn readout(&self, device: &DeviceInfo) -> Option<VictronReadout> {
+        let data = Self::payload(device)?;
+        let key = self.keys.get(&device.address().to_ascii_uppercase())?;
+        Self::decode(data[4], &Self::decrypt(data, key)?)
+    }
+}
+
+impl PeripheralDecoder for VictronDecoder {
+    fn name(&self) -> &str {
+        "Victron"
+    }
+
+    fn category(&self) -> Option<DeviceCategory> {
+        Some(DeviceCategory::Energy)
+    }
+
+    fn summary(&self, device: &DeviceInfo) -> Option<String> {
+        Self::payload(device)?;
+        let Some(readout) = self.readout(device) else {
+            return Some("Victron (encrypted)".to_string());
+        };
+        Some(match readout.state_of_charge {
+            Some(soc) => format!("{:.2} V {:.1}%", readout.voltage, soc),
+            None => format!("{:.2} V {:.1} A", readout.voltage, readout.current),
+        })
+    }
+
+    fn details(&self, device: &DeviceInfo) -> Vec<DetailItem> {
+        let Some(readout) = self.readout(device) else {
+            return Vec::new();
+        };
+        let mut items = vec![
+            DetailItem {
+                label: "Victron voltage".to_string(),
+                value: format!("{:.2} V", readout.voltage),
+            },
+            DetailItem {
+                label: "Victron current".to_string(),
+                value: format!("{:.3} A", readout.current),
+            },
+        ];
+        if let Some(soc) = readout.state_of_charge {
+            items.push(DetailItem {
+                label: "Victron state of charge".to_string(),
+                value: format!("{soc:.1}%"),
+            });
+        }
+        items
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn device(id: &str) -> DeviceInfo {
+        DeviceInfo {
+            id: id.to_string(),
+            address: id.to_string(),
+            name: "Unknown".to_string(),
+            rssi: Some(-60),
+            rssi_smoothed: Some(-60),
+            connected: false,
+            tx_power_level: None,
+            address_type: None,
+            appearance: None,
+            connectable: None,
+            raw_adv: None,
+            manufacturer_data: BTreeMap::new(),
+            service_data: BTreeMap::new(),
+            services: Vec::new(),
+            adv_count: 1,
+            first_seen: UNIX_EPOCH,
+            last_seen: UNIX_EPOCH,
+        }
+    }
+
+    fn with_manufacturer_data(company_id: u16, data: &[u8]) -> DeviceInfo {
+        let mut device = device("AA:BB:CC:DD:EE:FF");
+        device.manufacturer_data.insert(company_id, data.to_vec());
+        device
+    }
+
+    fn with_service_data(uuid: &str, data: &[u8]) -> DeviceInfo {
+        let mut device = device("AA:BB:CC:DD:EE:FF");
+        device.service_data.insert(uuid.to_string(), data.to_vec());
+        device
+    }
+
+    fn hex(text: &str) -> Vec<u8> {
+        parse_hex_bytes(text).unwrap()
+    }
+
+    fn assert_close(actual: Option<f32>, expected: f32) {
+        let actual = actual.expect("value present");
+        assert!(
+            (actual - expected).abs() < 0.01,
+            "expected {expected}, got {actual}"
+        );
+    }
+
+    fn detail<'a>(items: &'a [DetailItem], label: &str) -> Option<&'a str> {
+        items
+            .iter()
+            .find(|item| item.label == label)
+            .map(|item| item.value.as_str())
+    }
+
+    // Test vectors from the Ruuvi data format 5 specification.
+    const RUUVI_VALID: &str = "0512FC5394C37C0004FFFC040CAC364200CDCBB8334C884F";
+    const RUUVI_MINIMUM: &str = "058001000000008001800180010000000000CBB8334C884F";
+    const RUUVI_INVALID: &str = "058000FFFFFFFF800080008000FFFFFFFFFFFFFFFFFFFFFF";
+
+    #[test]
+    fn ruuvi_format5_valid() {
+        let measurement = RuuviDecoder::decode_format5(&hex(RUUVI_VALID)).unwrap();
+        assert_close(measurement.temperature, 24.3);
+        assert_close(measurement.humidity, 53.49);
+        assert_close(measurement.pressure, 1000.44);
+        assert_eq!(measurement.acceleration, Some([4, -4, 1036]));
+        assert_eq!(measurement.battery_mv, Some(2977));
+        assert_eq!(measurement.tx_power, Some(4));
+        assert_eq!(measurement.movement_counter, Some(66));
+        assert_eq!(measurement.sequence, Some(205));
+    }
+
+    #[test]
+    fn ruuvi_format5_minimum() {
+        let measurement = RuuviDecoder::decode_format5(&hex(RUUVI_MINIMUM)).unwrap();
+        assert_close(measurement.temperature, -163.835);
+        assert_close(measurement.humidity, 0.0);
+        assert_close(measurement.pressure, 500.0);
+        assert_eq!(measurement.acceleration, Some([-32767, -32767, -32767]));
+        assert_eq!(measurement.battery_mv, Some(1600));
+        assert_eq!(measurement.tx_power, Some(-40));
+        assert_eq!(measurement.movement_counter, Some(0));
+        assert_eq!(measurement.sequence, Some(0));
+    }
+
+    #[test]
+    fn ruuvi_format5_invalid_sentinels() {
+        let measurement = RuuviDecoder::decode_format5(&hex(RUUVI_INVALID)).unwrap();
+        assert_eq!(measurement.temperature, None);
+        assert_eq!(measurement.humidity, None);
+        assert_eq!(measurement.pressure, None);
+        assert_eq!(measurement.acceleration, None);
+        assert_eq!(measurement.battery_mv, None);
+        assert_eq!(measurement.tx_power, None);
+        assert_eq!(measurement.movement_counter, None);
+        assert_eq!(measurement.sequence, None);
+    }
+
+    #[test]
+    fn ruuvi_format5_rejects_other_formats() {
+        assert!(RuuviDecoder::decode_format5(&[]).is_none());
+        assert!(RuuviDecoder::decode_format5(&[0x05, 0x12, 0xfc]).is_none());
+        assert!(RuuviDecoder::decode_format5(&hex("0312FC5394C37C")).is_none());
+    }
+
+    #[test]
+    fn ruuvi_summary_and_details() {
+        let device = with_manufacturer_data(0x0499, &hex(RUUVI_VALID));
+        assert_eq!(
+            RuuviDecoder.summary(&device).as_deref(),
+            Some("24.3 C 53.5%")
+        );
+        let details = RuuviDecoder.details(&device);
+        assert_eq!(detail(&details, "Ruuvi temperature"), Some("24.3 C"));
+        assert_eq!(detail(&details, "Ruuvi humidity"), Some("53.5%"));
+        assert_eq!(detail(&details, "Ruuvi pressure"), Some("1000.44 hPa"));
+        assert_eq!(
+            detail(&details, "Ruuvi acceleration"),
+            Some("x 4 mG, y -4 mG, z 1036 mG")
+        );
+        assert_eq!(detail(&details, "Ruuvi battery"), Some("2977 mV"));
+        assert_eq!(detail(&details, "Ruuvi tx power"), Some("4 dBm"));
+    }
+
+    #[test]
+    fn ruuvi_invalid_readings_have_no_summary() {
+        let device = with_manufacturer_data(0x0499, &hex(RUUVI_INVALID));
+        assert_eq!(RuuviDecoder.summary(&device), None);
+        assert!(RuuviDecoder.details(&device).is_empty());
+    }
+
+    #[test]
+    fn ruuvi_ignores_other_devices() {
+        let device = with_manufacturer_data(0x004c, &hex(RUUVI_VALID));
+        assert_eq!(RuuviDecoder.summary(&device), None);
+        let device = with_service_data("0000feaa-0000-1000-8000-00805f9b34fb", &[0x05]);
+        assert!(RuuviDecoder.details(&device).is_empty());
+    }
+
+    #[test]
+    fn hex_bytes_formats_lowercase_pairs() {
+        assert_eq!(hex_bytes(&[]), "");
+        assert_eq!(hex_bytes(&[0x0a]), "0a");
+        assert_eq!(hex_bytes(&[0x00, 0xff, 0x10]), "00 ff 10");
+    }
+
+    #[test]
+    fn hex_display_marks_empty_payloads() {
+        assert_eq!(hex_display(&[]), "(empty)");
+        assert_eq!(hex_display(&[0x0a]), "0a");
+    }
+
+    #[test]
+    fn bit_string_empty_and_single_byte() {
+        assert_eq!(bit_string(&[]), "(empty)");
+        assert_eq!(bit_string(&[0x40]), "01000000");
+        assert_eq!(bit_string(&[0x44, 0x01]), "01000100 00000001");
+    }
+
+    #[test]
+    fn hex_dump_empty() {
+        assert_eq!(hex_dump(&[]), "");
+    }
+
+    #[test]
+    fn hex_dump_partial_row() {
+        assert_eq!(
+            hex_dump(b"Hi\x00\xff"),
+            "00000000: 48 69 00 ff                                      Hi.."
+        );
+    }
+
+    #[test]
+    fn hex_dump_multiple_rows() {
+        let bytes = (0x30..0x30 + 18).collect::<Vec<u8>>();
+        let dump = hex_dump(&bytes);
+        let rows = dump.lines().collect::<Vec<_>>();
+        assert_eq!(
+            rows,
+            [
+                "00000000: 30 31 32 33 34 35 36 37 38 39 3a 3b 3c 3d 3e 3f  0123456789:;<=>?",
+                "00000010: 40 41                                            @A",
+            ]
+        );
+    }
+
+    #[test]
+    fn format_age_sub_second_is_now() {
+        assert_eq!(format_age(Duration::ZERO), "now");
+        assert_eq!(format_age(Duration::from_millis(999)), "now");
+        assert_eq!(format_age(Duration::from_secs(1)), "1s");
+    }
+
+    #[test]
+    fn format_age_minutes() {
+        assert_eq!(format_age(Duration::from_secs(59)), "59s");
+        assert_eq!(format_age(Duration::from_secs(60)), "1m");
+        assert_eq!(format_age(Duration::from_secs(3599)), "59m");
+    }
+
+    #[test]
+    fn format_age_hours_and_days() {
+        assert_eq!(format_age(Duration::from_secs(3600)), "1h");
+        assert_eq!(format_age(Duration::from_secs(86399)), "23h");
+        assert_eq!(format_age(Duration::from_secs(86400)), "1d");
+    }
+
+    #[test]
+    fn ad_structures_eddystone_url() {
+        // Flags, a complete 16-bit service list and Eddystone-URL service data.
+        let adv = hex("02 01 06 03 03 aa fe 0d 16 aa fe 10 eb 03 67 6f 6f 67 6c 65 07");
+        assert_eq!(
+            parse_ad_structures(&adv),
+            vec![
+                AdStructure::Flags(0x06),
+                AdStructure::ServiceUuids16 {
+                    complete: true,
+                    uuids: vec![0xfeaa],
+                },
+                AdStructure::ServiceData16 {
+                    uuid: 0xfeaa,
+                    data: hex("10 eb 03 67 6f 6f 67 6c 65 07"),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn ad_structures_thingy_uuid_and_name() {
+        let adv = hex(
+            "02 01 06 11 07 42 00 74 a9 ff 52 10 9b 33 49 35 9b 00 01 68 ef \
+             07 09 54 68 69 6e 67 79",
+        );
+        assert_eq!(
+            parse_ad_structures(&adv),
+            vec![
+                AdStructure::Flags(0x06),
+                AdStructure::ServiceUuids128 {
+                    complete: true,
+                    uuids: vec![Uuid::parse_str("ef680100-9b35-4933-9b10-52ffa9740042").unwrap()],
+                },
+                AdStructure::LocalName {
+                    complete: true,
+                    name: "Thingy".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn ad_structures_ibeacon_with_tx_power() {
+        let adv = hex(
+            "02 01 06 02 0a 04 1a ff 4c 00 02 15 e2 c5 6d b5 df fb 48 d2 b0 60 d0 f5 a7 10 96 e0 \
+             00 01 00 02 c5",
+        );
+        let structures = parse_ad_structures(&adv);
+        assert_eq!(structures[1], AdStructure::TxPowerLevel(4));
+        assert_eq!(
+            structures[2],
+            AdStructure::ManufacturerData {
+                company_id: 0x004c,
+                data: hex("02 15 e2 c5 6d b5 df fb 48 d2 b0 60 d0 f5 a7 10 96 e0 00 01 00 02 c5"),
+            }
+        );
+        assert_eq!(structures.len(), 3);
+    }
+
+    #[test]
+    fn ad_structures_stop_at_padding() {
+        let adv = hex("02 01 06 00 00 00 00");
+        assert_eq!(parse_ad_structures(&adv), vec![AdStructure::Flags(0x06)]);
+    }
+
+    #[test]
+    fn ad_structures_drop_truncated_field() {
+        // The manufacturer data claims five bytes but only three follow.
+        let adv = hex("02 01 1a 05 ff 4c 00");
+        assert_eq!(parse_ad_structures(&adv), vec![AdStructure::Flags(0x1a)]);
+    }
+
+    #[test]
+    fn device_address_falls_back_to_id() {
+        let mut device = device("hci0/dev_AA_BB_CC_DD_EE_FF");
+        device.address = "AA:BB:CC:DD:EE:FF".to_string();
+        assert_eq!(device.address(), "AA:BB:CC:DD:EE:FF");
+        device.address.clear();
+        assert_eq!(device.address(), "hci0/dev_AA_BB_CC_DD_EE_FF");
+    }
+
+    #[test]
+    fn victron_key_matches_bluetooth_address() {
+        let key = [0x5a; 16];
+        // Solar charger record: 13.50 V, 2.5 A. CTR mode, so decrypting the plaintext encrypts it.
+        let mut data = vec![0x10, 0x00, 0xa0, 0x42, 0x01, 0x34, 0x12, key[0]];
+        data.extend_from_slice(&[0x00, 0x00, 0x46, 0x05, 0x19, 0x00]);
+        let encrypted = VictronDecoder::decrypt(&data, &key).unwrap();
+        data.truncate(8);
+        data.extend_from_slice(&encrypted);
+
+        let mut device = with_manufacturer_data(VICTRON_COMPANY_ID, &data);
+        device.id = "hci0/dev_AA_BB_CC_DD_EE_FF".to_string();
+        let keys = HashMap::from([("aa:bb:cc:dd:ee:ff".to_string(), key)]);
+        let decoders = DecoderRegistry::with_victron_keys(keys).build();
+        let summary = decoders.iter().find_map(|decoder| decoder.summary(&device));
+        assert_eq!(summary.as_deref(), Some("13.50 V 2.5 A"));
+    }
+
+    #[test]
+    fn bthome_skips_newer_and_device_info_objects() {
+        // Device type (0xf0), firmware (0xf2), conductivity (0x56), direction (0x5e) and
+        // channel (0x60) come before the temperature and humidity.
+        let data = hex("40f00100f2000104560a005e5a00600202ca0803bf13");
+        let measurement = BtHomeDecoder::decode(&data).unwrap();
+        assert_close(measurement.temperature, 22.5);
+        assert_close(measurement.humidity, 50.55);
+    }
+
+    #[test]
+    fn switchbot_meter_reading() {
+        let uuid = "0000fd3d-0000-1000-8000-00805f9b34fb";
+        let device = with_service_data(uuid, &hex("54006405962c"));
+        let summary = SwitchBotDecoder.summary(&device);
+        assert_eq!(summary.as_deref(), Some("22.5 C 44%"));
+        let details = SwitchBotDecoder.details(&device);
+        assert_eq!(detail(&details, "SwitchBot battery"), Some("100%"));
+    }
+
+    #[test]
+    fn mi_flora_moisture_reading() {
+        let data = hex("71209800a864aed0a8654c0d08100112");
+        let device = with_service_data(MIBEACON_SERVICE_UUID, &data);
+        let details = MiFloraDecoder.details(&device);
+        assert_eq!(detail(&details, "Mi Flora moisture"), Some("18%"));
+    }
+
+    #[test]
+    fn sensorpush_ht_w_reading() {
+        // HT.w header, then 21.5 C and 45% packed as mixed-radix digits.
+        let mut device = with_manufacturer_data(0x6804, &hex("17d046"));
+        device.name = "SensorPush HT.w".to_string();
+        let summary = SensorPushDecoder.summary(&device);
+        assert_eq!(summary.as_deref(), Some("SensorPush 21.5 C 45.0%"));
+        let details = SensorPushDecoder.details(&device);
+        assert_eq!(detail(&details, "SensorPush model"), Some("HT.w"));
+    }
+
+    #[test]
+    fn find_my_separated_and_nearby() {
+        let mut separated = hex("1219");
+        separated.push(0x10);
+        separated.extend_from_slice(&[0xaa; 24]);
+        let device = with_manufacturer_data(APPLE_COMPANY_ID, &separated);
+        let summary = FindMyDecoder.summary(&device);
+        let expected = "Find My AirTag separated from owner";
+        assert_eq!(summary.as_deref(), Some(expected));
+
+        let device = with_manufacturer_data(APPLE_COMPANY_ID, &hex("12025400"));
+        let details = FindMyDecoder.details(&device);
+        assert_eq!(detail(&details, "Find My mode"), Some("owner nearby"));
+        assert_eq!(detail(&details, "Find My battery"), Some("medium"));
+    }
+
+    #[test]
+    fn govee_h5179_reading() {
+        let data = hex("01000101ca08a81664");
+        let device = with_manufacturer_data(GOVEE_H5179_COMPANY_ID, &data);
+        let summary = GoveeH5179Decoder.summary(&device);
+        assert_eq!(summary.as_deref(), Some("22.5 C 58.0%"));
+        let details = GoveeH5179Decoder.details(&device);
+        assert_eq!(detail(&details, "Govee battery"), Some("100%"));
+    }
+
+    #[test]
+    fn thingy_recognized_by_configuration_service() {
+        let mut device = device("AA:BB:CC:DD:EE:FF");
+        assert_eq!(ThingyDecoder.summary(&device), None);
+        device.services = vec![THINGY_CONFIGURATION_SERVICE_UUID.to_uppercase()];
+        let summary = ThingyDecoder.summary(&device);
+        assert_eq!(summary.as_deref(), Some("Thingy:52"));
+    }
+
+    #[test]
+    fn oral_b_running() {
+        let device = with_manufacturer_data(ORALB_COMPANY_ID, &hex("062a320380011e03000004"));
+        let summary = OralBDecoder.summary(&device);
+        assert_eq!(summary.as_deref(), Some("Oral-B brushing, 0:30 left"));
+        let details = OralBDecoder.details(&device);
+        assert_eq!(detail(&details, "Oral-B brushing time"), Some("1:30"));
+        assert_eq!(detail(&details, "Oral-B mode"), Some("massage"));
+        assert_eq!(detail(&details, "Oral-B pressure"), Some("high"));
+        assert_eq!(detail(&details, "Oral-B sector"), Some("0"));
+    }
+
+    #[test]
+    fn oral_b_idle() {
+        let device = with_manufacturer_data(ORALB_COMPANY_ID, &hex("062a320200000001ff"));
+        let summary = OralBDecoder.summary(&device);
+        assert_eq!(summary.as_deref(), Some("Oral-B idle"));
+        let details = OralBDecoder.details(&device);
+        assert_eq!(detail(&details, "Oral-B sector"), Some("none"));
+    }
+
+    #[test]
+    fn oral_b_rejects_other_lengths() {
+        let device = with_manufacturer_data(ORALB_COMPANY_ID, &hex("062a320200000001ff00"));
+        assert_eq!(OralBDecoder.summary(&device), None);
+    }
+
+    #[test]
+    fn scan_config_forgets_silent_devices() {
+        let config = ScanConfig {
+            forget_after: Duration::from_secs(60),
+            ..ScanConfig::default()
+        };
+        let mut device = device("AA:BB:CC:DD:EE:FF");
+        let now = UNIX_EPOCH + Duration::from_secs(1000);
+        device.last_seen = now - Duration::from_secs(30);
+        assert!(!config.forgets(&device, now));
+        device.last_seen = now - Duration::from_secs(90);
+        assert!(config.forgets(&device, now));
+    }
+
+    #[test]
+    fn scan_config_name_filter_matches_address() {
+        let config = ScanConfig {
+            name_filter: Some("aa:bb".to_string()),
+            ..ScanConfig::default()
+        };
+        let mut device = device("hci0/dev_AA_BB_CC_DD_EE_FF");
+        device.address = "AA:BB:CC:DD:EE:FF".to_string();
+        assert!(config.accepts(&device, UNIX_EPOCH));
+        device.address = "11:22:33:44:55:66".to_string();
+        assert!(!config.accepts(&device, UNIX_EPOCH));
+    }
+
+    #[test]
+    fn csv_record_uses_bluetooth_address() {
+        let mut device = device("hci0/dev_AA_BB_CC_DD_EE_FF");
+        device.address = "AA:BB:CC:DD:EE:FF".to_string();
+        let record = csv_record(&device, &[]);
+        assert!(record.starts_with("AA:BB:CC:DD:EE:FF,Unknown,-60,"));
+    }
+
+    #[test]
+    fn switchbot_bot_switch_mode() {
+        let uuid = "0000fd3d-0000-1000-8000-00805f9b34fb";
+        let device = with_service_data(uuid, &hex("488064"));
+        assert_eq!(SwitchBotDecoder.summary(&device).as_deref(), Some("Bot on"));
+        let details = SwitchBotDecoder.details(&device);
+        assert_eq!(detail(&details, "SwitchBot mode"), Some("switch"));
+        let device = with_service_data(uuid, &hex("48c064"));
+        let summary = SwitchBotDecoder.summary(&device);
+        assert_eq!(summary.as_deref(), Some("Bot off"));
+    }
+
+    #[test]
+    fn switchbot_bot_press_mode_is_off() {
+        let uuid = "0000fd3d-0000-1000-8000-00805f9b34fb";
+        for flags in ["480064", "484064"] {
+            let device = with_service_data(uuid, &hex(flags));
+            let summary = SwitchBotDecoder.summary(&device);
+            assert_eq!(summary.as_deref(), Some("Bot off"));
+            let details = SwitchBotDecoder.details(&device);
+            assert_eq!(detail(&details, "SwitchBot mode"), Some("press"));
+        }
+    }
+
+    #[test]
+    fn eddystone_url_uid_and_tlm_frames() {
+        let url = with_service_data(EDDYSTONE_SERVICE_UUID, &hex("10eb03676f6f676c6507"));
+        let summary = EddystoneDecoder.summary(&url);
+        assert_eq!(summary.as_deref(), Some("https://google.com"));
+
+        let uid = hex("00e7 8b0ca750e77f59a8a3a7 1234567890ab");
+        let device = with_service_data(EDDYSTONE_SERVICE_UUID, &uid);
+        let summary = EddystoneDecoder.summary(&device);
+        let expected = "8b0ca750e77f59a8a3a7/1234567890ab";
+        assert_eq!(summary.as_deref(), Some(expected));
+        let details = EddystoneDecoder.details(&device);
+        assert_eq!(detail(&details, "Eddystone ranging data"), Some("-25 dBm"));
+
+        let tlm = hex("2000 0bb8 1700 0000000a 00000064");
+        let device = with_service_data(EDDYSTONE_SERVICE_UUID, &tlm);
+        let summary = EddystoneDecoder.summary(&device);
+        assert_eq!(summary.as_deref(), Some("3.00V 23.0C"));
+        let details = EddystoneDecoder.details(&device);
+        assert_eq!(detail(&details, "Eddystone adv count"), Some("10"));
+        assert_eq!(detail(&details, "Eddystone uptime"), Some("10.0 s"));
+    }
+
+    #[test]
+    fn ibeacon_reading() {
+        let data = hex("0215 e2c56db5dffb48d2b060d0f5a71096e0 0001 0002 c5");
+        let device = with_manufacturer_data(APPLE_COMPANY_ID, &data);
+        let summary = IBeaconDecoder.summary(&device);
+        let expected = "e2c56db5-dffb-48d2-b060-d0f5a71096e0";
+        assert_eq!(summary.as_deref(), Some(expected));
+        let details = IBeaconDecoder.details(&device);
+        assert_eq!(detail(&details, "iBeacon major"), Some("1"));
+        assert_eq!(detail(&details, "iBeacon minor"), Some("2"));
+        assert_eq!(detail(&details, "iBeacon measured power"), Some("-59 dBm"));
+    }
+
+    #[test]
+    fn altbeacon_reading() {
+        // The example beacon from the AltBeacon specification.
+        let data = hex("beac 2f234454cf6d4a0fadf2f4911ba9ffa600010002 c5 00");
+        let device = with_manufacturer_data(0x0118, &data);
+        let summary = AltBeaconDecoder.summary(&device);
+        assert_eq!(summary.as_deref(), Some("AltBeacon 2f234454..."));
+        let details = AltBeaconDecoder.details(&device);
+        let id = "2f234454cf6d4a0fadf2f4911ba9ffa600010002";
+        assert_eq!(detail(&details, "AltBeacon ID"), Some(id));
+        let reference = detail(&details, "AltBeacon reference RSSI");
+        assert_eq!(reference, Some("-59 dBm"));
+    }
+
+    #[test]
+    fn ruuvi_format3_valid() {
+        // Test vector from the Ruuvi data format 3 specification.
+        let measurement = RuuviDecoder::decode(&hex("03291A1ECE1EFC18F94202CA0B53")).unwrap();
+        assert_close(measurement.temperature, 26.3);
+        assert_close(measurement.humidity, 20.5);
+        assert_close(measurement.pressure, 1027.66);
+        assert_eq!(measurement.acceleration, Some([-1000, -1726, 714]));
+        assert_eq!(measurement.battery_mv, Some(2899));
+    }
+
+    #[test]
+    fn mibeacon_temperature_and_humidity() {
+        // Unencrypted LYWSDCD2 frame with a MAC and a combined temperature/humidity object.
+        let data = hex("5020aa01 2e ffeeddccbbaa 0d1004 d200c201");
+        let device = with_service_data(MIBEACON_SERVICE_UUID, &data);
+        let summary = MiBeaconDecoder.summary(&device);
+        assert_eq!(summary.as_deref(), Some("21.0 C 45%"));
+
+        let data = hex("5020aa01 2f ffeeddccbbaa 0a1001 5d");
+        let device = with_service_data(MIBEACON_SERVICE_UUID, &data);
+        let details = MiBeaconDecoder.details(&device);
+        assert_eq!(detail(&details, "MiBeacon battery"), Some("93%"));
+    }
+
+    #[test]
+    fn atc_and_pvvx_readings() {
+        let data = hex("a4c138aabbcc 00e6 2e 5f 0b86 0c");
+        let device = with_service_data(ENVIRONMENTAL_SENSING_SERVICE_UUID, &data);
+        assert_eq!(AtcDecoder.summary(&device).as_deref(), Some("23.0 C 46%"));
+        let details = AtcDecoder.details(&device);
+        assert_eq!(detail(&details, "ATC battery voltage"), Some("2950 mV"));
+
+        let data = hex("ccbbaa38c1a4 fa08 1c12 860b 5f 0c 04");
+        let device = with_service_data(ENVIRONMENTAL_SENSING_SERVICE_UUID, &data);
+        let details = AtcDecoder.details(&device);
+        assert_eq!(detail(&details, "Firmware"), Some("pvvx"));
+        assert_eq!(detail(&details, "pvvx temperature"), Some("22.98 C"));
+        assert_eq!(detail(&details, "pvvx humidity"), Some("46.36%"));
+        assert_eq!(detail(&details, "pvvx battery"), Some("95%"));
+    }
+
+    #[test]
+    fn govee_h5075_reading() {
+        let device = with_manufacturer_data(GOVEE_COMPANY_ID, &hex("00034187 6400"));
+        let summary = GoveeDecoder.summary(&device);
+        assert_eq!(summary.as_deref(), Some("21.3 C 38.3%"));
+        let details = GoveeDecoder.details(&device);
+        assert_eq!(detail(&details, "Govee battery"), Some("100%"));
+
+        // The top bit of the packed value marks a negative temperature.
+        let device = with_manufacturer_data(GOVEE_COMPANY_ID, &hex("00818d12 5a00"));
+        let summary = GoveeDecoder.summary(&device);
+        assert_eq!(summary.as_deref(), Some("-10.1 C 65.0%"));
+    }
+
+    #[test]
+    fn inkbird_reading() {
+        // The temperature (23.30 C) takes the place of the company id.
+        let mut device = with_manufacturer_data(0x091a, &hex("8a13 00a2b3 5a 08"));
+        device.name = "sps".to_string();
+        let summary = InkbirdDecoder.summary(&device);
+        assert_eq!(summary.as_deref(), Some("23.3 C 50.0%"));
+        let details = InkbirdDecoder.details(&device);
+        assert_eq!(detail(&details, "Inkbird battery"), Some("90%"));
+
+        device.name = "Unknown".to_string();
+        assert_eq!(InkbirdDecoder.summary(&device), None);
+    }
+
+    #[test]
+    fn tilt_and_tilt_pro_readings() {
+        let red = "0215 a495bb10c5b14b44b5121370f02d74de";
+        let data = hex(&format!("{red} 0044 041a c5"));
+        let device = with_manufacturer_data(APPLE_COMPANY_ID, &data);
+        let summary = TiltDecoder.summary(&device);
+        assert_eq!(summary.as_deref(), Some("Red 68F 1.050"));
+
+        // The Tilt Pro sends tenths of a degree and a fourth decimal of gravity.
+        let data = hex(&format!("{red} 02ad 2904 c5"));
+        let device = with_manufacturer_data(APPLE_COMPANY_ID, &data);
+        let details = TiltDecoder.details(&device);
+        assert_eq!(detail(&details, "Tilt temperature"), Some("68.5 F"));
+        assert_eq!(detail(&details, "Tilt specific gravity"), Some("1.050"));
+    }
+
+    #[test]
+    fn swift_pair_le_and_dual_mode() {
+        let data = [hex("030080").as_slice(), b"Mouse"].concat();
+        let device = with_manufacturer_data(MICROSOFT_COMPANY_ID, &data);
+        let summary = SwiftPairDecoder.summary(&device);
+        assert_eq!(summary.as_deref(), Some("Swift Pair \"Mouse\""));
+
+        let data = [hex("030280 7c0500").as_slice(), b"Keyboard"].concat();
+        let device = with_manufacturer_data(MICROSOFT_COMPANY_ID, &data);
+        let details = SwiftPairDecoder.details(&device);
+        let scenario = detail(&details, "Swift Pair scenario");
+        assert_eq!(scenario, Some("LE and BR/EDR"));
+        assert_eq!(detail(&details, "Swift Pair name"), Some("Keyboard"));
+    }
+
+    #[test]
+    fn apple_continuity_message_types() {
+        // A Handoff message followed by Nearby Info, as an unlocked iPhone sends them.
+        let data = hex("0c0e 00a71f3c4b526e9d0b8a2c31e580 1005 0318c05f2b");
+        let device = with_manufacturer_data(APPLE_COMPANY_ID, &data);
+        let summary = AppleContinuityDecoder.summary(&device);
+        assert_eq!(summary.as_deref(), Some("Apple Handoff, Nearby"));
+        assert_eq!(AppleContinuityDecoder.details(&device).len(), 2);
     }
 }
@@ -1,21 +1,275 @@
-use std::io;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::io::{self, Write as _};
+use std::path::PathBuf;
 use std::sync::mpsc;
-use std::time::Duration;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
-use anyhow::{Context, Result};
-use bleah::{DetailItem, DeviceInfo, PeripheralDecoder, ScanMessage};
-use crossterm::event::{self, Event, KeyCode};
+use anyhow::{Context, Result, bail};
+use bleah::influx::{InfluxSink, InfluxTarget};
+use bleah::pcap::PcapWriter;
+use bleah::rolling::RollingLog;
+use bleah::sink::{CsvSnapshot, JsonSnapshot, NdjsonWriter};
+use bleah::{
+    DecoderRegistry, DetailItem, DeviceCategory, DeviceInfo, DeviceRecord, GattCharacteristic,
+    GattProfile, PeripheralDecoder, ScanCommand, ScanMessage, ScanSink, SharedDecoders,
+};
+use btleplug::api::{AddressType, CharPropFlags};
+use clap::Parser as _;
+use crossterm::event::{
+    self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyModifiers, MouseEvent,
+    MouseEventKind,
+};
 use crossterm::execute;
 use crossterm::terminal::{EnterAlternateScreen, LeaveAlternateScreen};
 use ratatui::prelude::*;
-use ratatui::widgets::{Block, Borders, Cell, Paragraph, Row, Table, TableState, Wrap};
-use tokio::sync::watch;
+use ratatui::widgets::{
+    Block, Borders, Cell, Clear, Paragraph, Row, Sparkline, Table, TableState, Wrap,
+};
+use tokio::sync::{mpsc as tokio_mpsc, watch};
+
+use crate::alert::{Alert, AlertWatcher};
+use crate::cli::Cli;
+use crate::config::Config;
+use crate::theme::Theme;
+
+mod alert;
+mod cli;
+mod config;
+mod store;
+mod theme;
+
+const RSSI_HISTORY_LEN: usize = 60;
+const RSSI_METER_WIDTH: usize = 8;
+const VENDOR_WIDTH: usize = 14;
+const TRACKER_GLYPH: char = '⚠';
+const TABLE_COLUMNS: [&str; 8] = [
+    "",
+    "Address",
+    "Name",
+    "Vendor",
+    "RSSI",
+    "Signal",
+    "Seen",
+    "Connected",
+];
+const ROTATION_WINDOW: Duration = Duration::from_secs(15 * 60);
+const ALERT_FLASH: Duration = Duration::from_secs(5);
+
+const KEYBINDINGS: &[(&str, &[(&str, &str)])] = &[
+    (
+        "Navigation",
+        &[
+            ("up/down, j/k", "select previous/next device"),
+            ("g/G", "jump to first/last device"),
+            ("PgUp/PgDn", "move one page of devices"),
+            ("shift+PgUp/PgDn, K/J", "scroll details"),
+        ],
+    ),
+    (
+        "Filtering",
+        &[
+            ("/", "search by name or address"),
+            ("u", "toggle unnamed devices"),
+            ("o", "toggle connected devices only"),
+            ("s", "cycle sort order"),
+            ("S", "reverse sort direction"),
+            ("v", "group by manufacturer"),
+            ("p", "pause/resume updates"),
+        ],
+    ),
+    (
+        "Device",
+        &[
+            ("enter", "connect and list GATT services"),
+            ("enter, left/right", "expand/collapse a manufacturer group"),
+            ("b", "browse GATT characteristics (r/enter read, w write)"),
+            ("d", "toggle compact/verbose details"),
+            ("n", "set nickname"),
+            ("f", "pin/unpin"),
+            ("y", "copy address"),
+            ("Y", "copy device as JSON"),
+        ],
+    ),
+    (
+        "Export",
+        &[
+            ("e", "export JSON"),
+            ("E", "export selected device as JSON"),
+            ("c", "export CSV (visible devices)"),
+        ],
+    ),
+    ("General", &[("?", "toggle this help"), ("q/esc", "quit")]),
+];
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum SortMode {
+    #[default]
+    Name,
+    Rssi,
+    LastSeen,
+}
+
+impl SortMode {
+    fn next(self) -> Self {
+        match self {
+            SortMode::Name => SortMode::Rssi,
+            SortMode::Rssi => SortMode::LastSeen,
+            SortMode::LastSeen => SortMode::Name,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            SortMode::Name => "name",
+            SortMode::Rssi => "rssi",
+            SortMode::LastSeen => "last seen",
+        }
+    }
+
+    // Names read best A to Z; signal and recency are most useful strongest/newest first.
+    fn default_desc(self) -> bool {
+        self != SortMode::Name
+    }
+
+    // Index into TABLE_COLUMNS, for the header arrow.
+    fn column(self) -> usize {
+        match self {
+            SortMode::Name => 2,
+            SortMode::Rssi => 4,
+            SortMode::LastSeen => 6,
+        }
+    }
+
+    fn sort(self, devices: &mut [DeviceInfo], desc: bool) {
+        devices.sort_by(|a, b| {
+            let order = match self {
+                SortMode::Name => a.name.cmp(&b.name),
+                SortMode::Rssi => a.rssi_smoothed.cmp(&b.rssi_smoothed),
+                SortMode::LastSeen => a.last_seen.cmp(&b.last_seen),
+            };
+            let order = if desc { order.reverse() } else { order };
+            // Unknown RSSI sorts last whichever way the list runs.
+            let unknown = match self {
+                SortMode::Rssi => a.rssi_smoothed.is_none().cmp(&b.rssi_smoothed.is_none()),
+                _ => Ordering::Equal,
+            };
+            unknown.then(order).then(a.id.cmp(&b.id))
+        });
+    }
+}
+
+#[derive(Debug, Default)]
+struct Filters {
+    search: String,
+    hide_unknown: bool,
+    connected_only: bool,
+    min_rssi: Option<i16>,
+}
+
+impl Filters {
+    // Every active filter must accept a device for it to be listed.
+    fn accepts(&self, device: &DeviceInfo, nickname: Option<&str>) -> bool {
+        let search = self.search.to_lowercase();
+        let named = !(self.hide_unknown && device.name == "Unknown");
+        let connected = !self.connected_only || device.connected;
+        // Unknown RSSI is left to the scan loop's keep_unknown_rssi setting.
+        let strong = self
+            .min_rssi
+            .zip(device.rssi)
+            .is_none_or(|(min_rssi, rssi)| rssi >= min_rssi);
+        let searched = search.is_empty()
+            || device.name.to_lowercase().contains(&search)
+            || device.address().to_lowercase().contains(&search)
+            || nickname.is_some_and(|nickname| nickname.to_lowercase().contains(&search));
+        named && connected && strong && searched
+    }
+}
+
+// Samples are only taken when the device has been heard from since the last one, so a
+// device that stopped advertising doesn't fill the sparkline with its final reading.
+#[derive(Debug, Default)]
+struct RssiHistory {
+    last_seen: Option<SystemTime>,
+    samples: VecDeque<i16>,
+}
+
+impl RssiHistory {
+    fn record(&mut self, rssi: i16, last_seen: SystemTime) {
+        if self.last_seen.is_some_and(|sampled| last_seen <= sampled) {
+            return;
+        }
+        self.last_seen = Some(last_seen);
+        if self.samples.len() == RSSI_HISTORY_LEN {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(rssi);
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum InputMode {
+    #[default]
+    Normal,
+    Search,
+    Nickname,
+    Help,
+    Gatt,
+    GattWrite,
+}
+
+// A manufacturer group is keyed by its first company id; None collects devices without
+// manufacturer data.
+type GroupKey = Option<u16>;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ListRow {
+    Group { key: GroupKey, count: usize },
+    // Index into AppState::devices.
+    Device(usize),
+}
+
+fn group_key(device: &DeviceInfo) -> GroupKey {
+    device.manufacturer_data.keys().next().copied()
+}
 
 struct AppState {
+    all_devices: Vec<DeviceInfo>,
     devices: Vec<DeviceInfo>,
+    rows: Vec<ListRow>,
+    grouped: bool,
+    expanded: HashSet<GroupKey>,
+    rotating: HashSet<String>,
+    flashing: HashMap<String, Instant>,
     status: String,
     selected_id: Option<String>,
     table_state: TableState,
+    rssi_history: HashMap<String, RssiHistory>,
+    sort_key: SortMode,
+    sort_desc: bool,
+    input_mode: InputMode,
+    filters: Filters,
+    rssi_meter: (i16, i16),
+    reference_power: i16,
+    paused: bool,
+    pending: Option<Vec<DeviceInfo>>,
+    details_scroll: u16,
+    verbose_details: bool,
+    clipboard: Option<arboard::Clipboard>,
+    gatt: Option<GattProfile>,
+    gatt_cursor: usize,
+    // Keyed by (service, characteristic): the same characteristic UUID can appear under
+    // several services.
+    gatt_values: HashMap<(String, String), Result<Vec<u8>, String>>,
+    write_input: String,
+    nicknames: BTreeMap<String, String>,
+    nickname_input: String,
+    pinned: BTreeSet<String>,
+    seen_ids: HashSet<String>,
+    decoded_count: usize,
+    last_poll: Option<Instant>,
+    table_area: Rect,
+    details_area: Rect,
 }
 
 impl AppState {
@@ -23,194 +277,1288 @@ impl AppState {
         let mut table_state = TableState::default();
         table_state.select(Some(0));
         Self {
+            all_devices: Vec::new(),
             devices: Vec::new(),
+            rows: Vec::new(),
+            grouped: false,
+            expanded: HashSet::new(),
+            rotating: HashSet::new(),
+            flashing: HashMap::new(),
             status: "Starting scan...".to_string(),
             selected_id: None,
             table_state,
+            rssi_history: HashMap::new(),
+            sort_key: SortMode::default(),
+            sort_desc: SortMode::default().default_desc(),
+            input_mode: InputMode::default(),
+            filters: Filters::default(),
+            rssi_meter: Config::default().rssi_meter(),
+            reference_power: bleah::DEFAULT_REFERENCE_POWER,
+            paused: false,
+            pending: None,
+            details_scroll: 0,
+            verbose_details: true,
+            clipboard: None,
+            gatt: None,
+            gatt_cursor: 0,
+            gatt_values: HashMap::new(),
+            write_input: String::new(),
+            nicknames: store::load_nicknames(),
+            nickname_input: String::new(),
+            pinned: store::load_pinned(),
+            seen_ids: HashSet::new(),
+            decoded_count: 0,
+            last_poll: None,
+            table_area: Rect::default(),
+            details_area: Rect::default(),
         }
     }
 
-    fn apply(&mut self, msg: ScanMessage) {
+    fn apply(&mut self, msg: ScanMessage, decoders: &[Box<dyn PeripheralDecoder>]) {
         match msg {
-            ScanMessage::Devices(mut devices) => {
-                devices.sort_by(|a, b| a.name.cmp(&b.name).then(a.id.cmp(&b.id)));
-                let selected_id = self
-                    .selected_id
-                    .clone()
-                    .or_else(|| self.selected_device().map(|device| device.id.clone()));
-                self.devices = devices;
-                let selected_index = selected_id
-                    .as_ref()
-                    .and_then(|id| self.devices.iter().position(|device| device.id == *id))
-                    .or_else(|| if self.devices.is_empty() { None } else { Some(0) });
-                self.table_state.select(selected_index);
-                self.selected_id = selected_index
-                    .and_then(|index| self.devices.get(index))
-                    .map(|device| device.id.clone());
-                self.status = "Scanning...".to_string();
+            ScanMessage::Devices(devices) => {
+                self.record_rssi(&devices);
+                self.record_stats(&devices, decoders);
+                if self.paused {
+                    self.pending = Some(devices);
+                    return;
+                }
+                self.all_devices = devices;
+                self.status = if self.refresh() {
+                    "Selected device lost".to_string()
+                } else {
+                    "Scanning...".to_string()
+                };
             }
             ScanMessage::Status(status) => self.status = status,
+            ScanMessage::Gatt(profile) => {
+                self.status = format!("Connected to {}", profile.id);
+                self.gatt = Some(profile);
+                self.gatt_values.clear();
+            }
+            ScanMessage::HeartRate { id, bpm } => {
+                if let Some(gatt) = self.gatt.as_mut().filter(|gatt| gatt.id == id) {
+                    gatt.heart_rate = Some(bpm);
+                }
+            }
+            ScanMessage::CharacteristicValue {
+                id,
+                service,
+                characteristic,
+                value,
+            } => {
+                if self.gatt.as_ref().is_none_or(|gatt| gatt.id != id) {
+                    return;
+                }
+                let label = characteristic_label(&characteristic);
+                self.status = match &value {
+                    Ok(bytes) => format!("Read {label}: {} bytes", bytes.len()),
+                    Err(err) => format!("Read {label} failed: {err}"),
+                };
+                self.gatt_values.insert((service, characteristic), value);
+            }
+        }
+    }
+
+    fn raise_alert(&mut self, alert: Alert) {
+        self.flashing.insert(alert.id, Instant::now());
+        self.status = alert.message;
+    }
+
+    // Alerted rows blink for a few seconds.
+    fn flash_on(&self, id: &str) -> bool {
+        self.flashing.get(id).is_some_and(|at| {
+            let elapsed = at.elapsed();
+            elapsed < ALERT_FLASH && (elapsed.as_millis() / 500).is_multiple_of(2)
+        })
+    }
+
+    fn connect_selected(&mut self, commands: &tokio_mpsc::UnboundedSender<ScanCommand>) {
+        let Some(id) = self.selected_id.clone() else {
+            return;
+        };
+        if self.selected_device().and_then(|device| device.connectable) == Some(false) {
+            self.status = format!("{id} does not accept connections");
+            return;
+        }
+        self.status = format!("Connecting to {id}...");
+        let _ = commands.send(ScanCommand::Connect(id));
+    }
+
+    fn release_connection(&mut self, commands: &tokio_mpsc::UnboundedSender<ScanCommand>) {
+        if let Some(gatt) = &self.gatt
+            && self.selected_id.as_ref() != Some(&gatt.id)
+        {
+            let _ = commands.send(ScanCommand::Disconnect(gatt.id.clone()));
+            self.gatt = None;
+        }
+    }
+
+    fn selected_gatt(&self) -> Option<&GattProfile> {
+        self.gatt
+            .as_ref()
+            .filter(|gatt| self.selected_id.as_ref() == Some(&gatt.id))
+    }
+
+    // Characteristics of the connected device in browser order, with their service UUID.
+    fn gatt_characteristics(&self) -> Vec<(&str, &GattCharacteristic)> {
+        let Some(gatt) = self.selected_gatt() else {
+            return Vec::new();
+        };
+        gatt.services
+            .iter()
+            .flat_map(|service| {
+                let uuid = service.uuid.as_str();
+                let characteristics = service.characteristics.iter();
+                characteristics.map(move |found| (uuid, found))
+            })
+            .collect()
+    }
+
+    fn start_gatt_browser(&mut self) {
+        if self.gatt_characteristics().is_empty() {
+            self.status = "Connect (enter) to browse characteristics".to_string();
+            return;
+        }
+        self.gatt_cursor = 0;
+        self.input_mode = InputMode::Gatt;
+    }
+
+    fn select_next_characteristic(&mut self) {
+        let count = self.gatt_characteristics().len();
+        self.gatt_cursor = (self.gatt_cursor + 1).min(count.saturating_sub(1));
+    }
+
+    fn select_previous_characteristic(&mut self) {
+        self.gatt_cursor = self.gatt_cursor.saturating_sub(1);
+    }
+
+    fn read_selected_characteristic(
+        &mut self,
+        commands: &tokio_mpsc::UnboundedSender<ScanCommand>,
+    ) {
+        let characteristics = self.gatt_characteristics();
+        let Some(&(service, characteristic)) = characteristics.get(self.gatt_cursor) else {
+            return;
+        };
+        let label = characteristic_label(&characteristic.uuid);
+        if !characteristic.properties.contains(CharPropFlags::READ) {
+            self.status = format!("{label} is not readable");
+            return;
+        }
+        let command = ScanCommand::Read {
+            id: self.selected_id.clone().unwrap_or_default(),
+            service: service.to_string(),
+            characteristic: characteristic.uuid.clone(),
+        };
+        let _ = commands.send(command);
+        self.status = format!("Reading {label}...");
+    }
+
+    fn start_characteristic_write(&mut self) {
+        let characteristics = self.gatt_characteristics();
+        let Some(&(_, characteristic)) = characteristics.get(self.gatt_cursor) else {
+            return;
+        };
+        let writable = CharPropFlags::WRITE | CharPropFlags::WRITE_WITHOUT_RESPONSE;
+        if !characteristic.properties.intersects(writable) {
+            let label = characteristic_label(&characteristic.uuid);
+            self.status = format!("{label} is not writable");
+            return;
+        }
+        self.write_input.clear();
+        self.input_mode = InputMode::GattWrite;
+    }
+
+    fn cancel_characteristic_write(&mut self) {
+        self.write_input.clear();
+        self.input_mode = InputMode::Gatt;
+    }
+
+    fn commit_characteristic_write(&mut self, commands: &tokio_mpsc::UnboundedSender<ScanCommand>) {
+        let value = match bleah::parse_hex_bytes(&self.write_input) {
+            Ok(value) if !value.is_empty() => value,
+            Ok(_) => {
+                self.status = "Nothing to write".to_string();
+                return;
+            }
+            // Leave the prompt open so the typo can be fixed.
+            Err(err) => {
+                self.status = format!("Invalid hex: {err}");
+                return;
+            }
+        };
+        let characteristics = self.gatt_characteristics();
+        let Some(&(service, characteristic)) = characteristics.get(self.gatt_cursor) else {
+            return;
+        };
+        let label = characteristic_label(&characteristic.uuid);
+        let command = ScanCommand::Write {
+            id: self.selected_id.clone().unwrap_or_default(),
+            service: service.to_string(),
+            characteristic: characteristic.uuid.clone(),
+            value,
+        };
+        let _ = commands.send(command);
+        self.status = format!("Writing to {label}...");
+        self.write_input.clear();
+        self.input_mode = InputMode::Gatt;
+    }
+
+    // Returns true when the previously selected device is no longer listed.
+    fn select_id(&mut self, selected_id: Option<String>) -> bool {
+        let found = selected_id.as_ref().and_then(|id| {
+            (0..self.rows.len()).find(|index| {
+                self.row_device(*index)
+                    .is_some_and(|device| device.id == *id)
+            })
+        });
+        let lost = selected_id.is_some() && found.is_none();
+        // Snap to the row the lost device occupied rather than jumping back to the top.
+        let selected_index = found.or_else(|| match self.rows.len() {
+            0 => None,
+            len => Some(self.table_state.selected().unwrap_or(0).min(len - 1)),
+        });
+        self.table_state.select(selected_index);
+        self.selected_id = selected_index
+            .and_then(|index| self.row_device(index))
+            .map(|device| device.id.clone());
+        lost
+    }
+
+    fn row_device(&self, index: usize) -> Option<&DeviceInfo> {
+        match self.rows.get(index)? {
+            ListRow::Device(device) => self.devices.get(*device),
+            ListRow::Group { .. } => None,
+        }
+    }
+
+    fn selected_group(&self) -> Option<GroupKey> {
+        match self.rows.get(self.table_state.selected()?)? {
+            ListRow::Group { key, .. } => Some(*key),
+            ListRow::Device(_) => None,
+        }
+    }
+
+    fn refresh(&mut self) -> bool {
+        let selected_id = self
+            .selected_id
+            .clone()
+            .or_else(|| self.selected_device().map(|device| device.id.clone()));
+        self.rotating = bleah::rotating_addresses(&self.all_devices, ROTATION_WINDOW);
+        self.devices = self.visible_devices();
+        self.rows = self.list_rows();
+        self.select_id(selected_id)
+    }
+
+    fn list_rows(&self) -> Vec<ListRow> {
+        if !self.grouped {
+            return (0..self.devices.len()).map(ListRow::Device).collect();
+        }
+        let mut groups: BTreeMap<GroupKey, Vec<usize>> = BTreeMap::new();
+        for (index, device) in self.devices.iter().enumerate() {
+            groups.entry(group_key(device)).or_default().push(index);
+        }
+        // Busiest vendors first; members keep the active sort order.
+        let mut groups = groups.into_iter().collect::<Vec<_>>();
+        groups.sort_by_key(|(_, members)| std::cmp::Reverse(members.len()));
+        let mut rows = Vec::new();
+        for (key, members) in groups {
+            rows.push(ListRow::Group {
+                key,
+                count: members.len(),
+            });
+            if self.expanded.contains(&key) {
+                rows.extend(members.into_iter().map(ListRow::Device));
+            }
+        }
+        rows
+    }
+
+    fn toggle_verbose_details(&mut self) {
+        self.verbose_details = !self.verbose_details;
+        self.details_scroll = 0;
+    }
+
+    fn toggle_grouped(&mut self) {
+        self.grouped = !self.grouped;
+        self.refresh();
+    }
+
+    // Acts on the selected group header, or the group of the selected device.
+    fn set_group_expanded(&mut self, expanded: Option<bool>) {
+        let key = match self.selected_group() {
+            Some(key) => key,
+            None => match self.selected_device() {
+                Some(device) if self.grouped => group_key(device),
+                _ => return,
+            },
+        };
+        let expanded = expanded.unwrap_or(!self.expanded.contains(&key));
+        if expanded {
+            self.expanded.insert(key);
+        } else {
+            self.expanded.remove(&key);
+        }
+        self.refresh();
+        // Keep the cursor on the header so the group can be toggled back.
+        if let Some(index) = self
+            .rows
+            .iter()
+            .position(|row| matches!(row, ListRow::Group { key: row_key, .. } if *row_key == key))
+        {
+            self.select_index(index);
+        }
+    }
+
+    fn activate_selected(&mut self, commands: &tokio_mpsc::UnboundedSender<ScanCommand>) {
+        if self.selected_group().is_some() {
+            self.set_group_expanded(None);
+        } else {
+            self.connect_selected(commands);
+        }
+    }
+
+    // The table and the selection both index into this list, so it is the only place
+    // filtering and ordering happen.
+    fn visible_devices(&self) -> Vec<DeviceInfo> {
+        let mut devices: Vec<DeviceInfo> = self
+            .all_devices
+            .iter()
+            .filter(|device| {
+                let nickname = self.nicknames.get(device.address()).map(String::as_str);
+                self.filters.accepts(device, nickname)
+            })
+            .cloned()
+            .collect();
+        self.sort_key.sort(&mut devices, self.sort_desc);
+        // Stable, so pinned devices keep the active sort order among themselves.
+        devices.sort_by_key(|device| !self.pinned.contains(device.address()));
+        devices
+    }
+
+    fn cycle_sort(&mut self) {
+        self.sort_key = self.sort_key.next();
+        self.sort_desc = self.sort_key.default_desc();
+        self.refresh();
+    }
+
+    fn reverse_sort(&mut self) {
+        self.sort_desc = !self.sort_desc;
+        self.refresh();
+    }
+
+    fn sort_arrow(&self) -> char {
+        if self.sort_desc { '▼' } else { '▲' }
+    }
+
+    fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+        if !self.paused
+            && let Some(devices) = self.pending.take()
+        {
+            self.all_devices = devices;
+            self.refresh();
+        }
+    }
+
+    fn toggle_hide_unknown(&mut self) {
+        self.filters.hide_unknown = !self.filters.hide_unknown;
+        self.refresh();
+    }
+
+    fn toggle_connected_only(&mut self) {
+        self.filters.connected_only = !self.filters.connected_only;
+        self.refresh();
+    }
+
+    fn push_filter_char(&mut self, c: char) {
+        self.filters.search.push(c);
+        self.refresh();
+    }
+
+    fn pop_filter_char(&mut self) {
+        self.filters.search.pop();
+        self.refresh();
+    }
+
+    fn clear_filter(&mut self) {
+        self.filters.search.clear();
+        self.input_mode = InputMode::Normal;
+        self.refresh();
+    }
+
+    // Pins are keyed by Bluetooth address, like nicknames.
+    fn toggle_pin(&mut self) {
+        let Some(address) = self.selected_device().map(DeviceInfo::address) else {
+            self.status = "No device selected".to_string();
+            return;
+        };
+        let address = address.to_string();
+        if !self.pinned.remove(&address) {
+            self.pinned.insert(address);
+        }
+        if let Err(err) = store::save_pinned(&self.pinned) {
+            self.status = format!("Saving pinned devices failed: {err}");
+        }
+        self.refresh();
+    }
+
+    // Nicknames are keyed by Bluetooth address, so they survive an adapter change and can be
+    // written by hand.
+    fn start_nickname(&mut self) {
+        let Some(address) = self.selected_device().map(DeviceInfo::address) else {
+            self.status = "No device selected".to_string();
+            return;
+        };
+        self.nickname_input = self.nicknames.get(address).cloned().unwrap_or_default();
+        self.input_mode = InputMode::Nickname;
+    }
+
+    fn cancel_nickname(&mut self) {
+        self.nickname_input.clear();
+        self.input_mode = InputMode::Normal;
+    }
+
+    fn commit_nickname(&mut self) {
+        self.input_mode = InputMode::Normal;
+        let nickname = std::mem::take(&mut self.nickname_input).trim().to_string();
+        let Some(address) = self.selected_device().map(DeviceInfo::address) else {
+            return;
+        };
+        let address = address.to_string();
+        if nickname.is_empty() {
+            self.nicknames.remove(&address);
+        } else {
+            self.nicknames.insert(address, nickname);
+        }
+        self.status = match store::save_nicknames(&self.nicknames) {
+            Ok(()) => "Nickname saved".to_string(),
+            Err(err) => format!("Saving nicknames failed: {err}"),
+        };
+        self.refresh();
+    }
+
+    fn display_name<'a>(&'a self, device: &'a DeviceInfo) -> &'a str {
+        self.nicknames
+            .get(device.address())
+            .map(String::as_str)
+            .unwrap_or(&device.name)
+    }
+
+    fn record_stats(&mut self, devices: &[DeviceInfo], decoders: &[Box<dyn PeripheralDecoder>]) {
+        self.seen_ids
+            .extend(devices.iter().map(|device| device.id.clone()));
+        self.decoded_count = devices
+            .iter()
+            .filter(|device| device_summary(device, decoders).is_some())
+            .count();
+        self.last_poll = Some(Instant::now());
+    }
+
+    fn record_rssi(&mut self, devices: &[DeviceInfo]) {
+        self.rssi_history
+            .retain(|id, _| devices.iter().any(|device| device.id == *id));
+        for device in devices {
+            let Some(rssi) = device.rssi else {
+                continue;
+            };
+            self.rssi_history
+                .entry(device.id.clone())
+                .or_default()
+                .record(rssi, device.last_seen);
         }
     }
 
     fn selected_device(&self) -> Option<&DeviceInfo> {
         self.table_state
             .selected()
-            .and_then(|index| self.devices.get(index))
+            .and_then(|index| self.row_device(index))
+    }
+
+    fn copy_selected_address(&mut self) {
+        let Some(address) = self.selected_device().map(DeviceInfo::address) else {
+            self.status = "No device selected".to_string();
+            return;
+        };
+        let address = address.to_string();
+        self.status = match self.set_clipboard(address.clone()) {
+            Ok(()) => format!("Copied {address} to clipboard"),
+            Err(err) => format!("Clipboard error: {err}"),
+        };
+    }
+
+    fn set_clipboard(&mut self, text: String) -> Result<(), arboard::Error> {
+        // The clipboard is kept alive because on X11 its contents vanish once the
+        // owning handle is dropped.
+        match &mut self.clipboard {
+            Some(clipboard) => clipboard.set_text(text),
+            None => {
+                let mut clipboard = arboard::Clipboard::new()?;
+                clipboard.set_text(text)?;
+                self.clipboard = Some(clipboard);
+                Ok(())
+            }
+        }
+    }
+
+    // The selected device together with its decoded readings, as pasted into bug reports.
+    fn selected_json(&self, decoders: &[Box<dyn PeripheralDecoder>]) -> Option<(String, String)> {
+        let device = self.selected_device()?;
+        let record = DeviceRecord::new(device, decoders, SystemTime::now());
+        let json = serde_json::to_string_pretty(&record).ok()?;
+        Some((device.id.clone(), json))
+    }
+
+    fn copy_selected_json(&mut self, decoders: &[Box<dyn PeripheralDecoder>]) {
+        let Some((id, json)) = self.selected_json(decoders) else {
+            self.status = "No device selected".to_string();
+            return;
+        };
+        self.status = match self.set_clipboard(json) {
+            Ok(()) => format!("Copied {id} as JSON to clipboard"),
+            Err(err) => format!("Clipboard error: {err}"),
+        };
+    }
+
+    fn export_selected_json(&mut self, decoders: &[Box<dyn PeripheralDecoder>]) {
+        let Some((id, json)) = self.selected_json(decoders) else {
+            self.status = "No device selected".to_string();
+            return;
+        };
+        let path = export_path("bleah-device", "json");
+        self.status = match std::fs::write(&path, json + "\n") {
+            Ok(()) => format!("Exported {id} to {}", path.display()),
+            Err(err) => format!("Export failed: {err}"),
+        };
+    }
+
+    fn export_json(&mut self) {
+        let path = export_path("bleah", "json");
+        self.status = match bleah::write_json(&self.all_devices, &path) {
+            Ok(()) => format!(
+                "Exported {} devices to {}",
+                self.all_devices.len(),
+                path.display()
+            ),
+            Err(err) => format!("Export failed: {err}"),
+        };
+    }
+
+    fn export_csv(&mut self, decoders: &[Box<dyn PeripheralDecoder>]) {
+        let path = export_path("bleah", "csv");
+        self.status = match bleah::write_csv(&self.devices, decoders, &path) {
+            Ok(()) => format!(
+                "Exported {} devices to {}",
+                self.devices.len(),
+                path.display()
+            ),
+            Err(err) => format!("Export failed: {err}"),
+        };
+    }
+
+    fn scroll_details_down(&mut self, lines: u16) {
+        self.details_scroll = self.details_scroll.saturating_add(lines);
+    }
+
+    fn scroll_details_up(&mut self, lines: u16) {
+        self.details_scroll = self.details_scroll.saturating_sub(lines);
     }
 
     fn select_next(&mut self) {
-        if self.devices.is_empty() {
+        if self.rows.is_empty() {
             self.table_state.select(None);
             self.selected_id = None;
             return;
         }
         let next = match self.table_state.selected() {
-            Some(index) => (index + 1) % self.devices.len(),
+            Some(index) => (index + 1) % self.rows.len(),
             None => 0,
         };
-        self.table_state.select(Some(next));
-        self.selected_id = self.devices.get(next).map(|device| device.id.clone());
+        self.select_index(next);
+    }
+
+    // Rows visible in the table as last rendered, minus the borders and the header.
+    fn page_size(&self) -> usize {
+        usize::from(self.table_area.height.saturating_sub(3)).max(1)
+    }
+
+    fn select_page_down(&mut self) {
+        let current = self.table_state.selected().unwrap_or(0);
+        self.select_index(current.saturating_add(self.page_size()));
+    }
+
+    fn select_page_up(&mut self) {
+        let current = self.table_state.selected().unwrap_or(0);
+        self.select_index(current.saturating_sub(self.page_size()));
+    }
+
+    fn select_first(&mut self) {
+        self.select_index(0);
+    }
+
+    fn select_last(&mut self) {
+        self.select_index(self.rows.len().saturating_sub(1));
+    }
+
+    fn select_index(&mut self, index: usize) {
+        if self.rows.is_empty() {
+            self.table_state.select(None);
+            self.selected_id = None;
+            return;
+        }
+        let index = index.min(self.rows.len() - 1);
+        self.details_scroll = 0;
+        self.table_state.select(Some(index));
+        self.selected_id = self.row_device(index).map(|device| device.id.clone());
+    }
+
+    fn handle_mouse(&mut self, mouse: MouseEvent) {
+        let over_details = area_contains(self.details_area, mouse.column, mouse.row);
+        match mouse.kind {
+            MouseEventKind::Down(_) if area_contains(self.table_area, mouse.column, mouse.row) => {
+                // Skip the top border and the header row.
+                let Some(row) = mouse.row.checked_sub(self.table_area.y + 2) else {
+                    return;
+                };
+                let index = self.table_state.offset() + usize::from(row);
+                if index < self.rows.len() {
+                    self.select_index(index);
+                }
+            }
+            MouseEventKind::ScrollDown if over_details => self.scroll_details_down(1),
+            MouseEventKind::ScrollUp if over_details => self.scroll_details_up(1),
+            MouseEventKind::ScrollDown => self.select_next(),
+            MouseEventKind::ScrollUp => self.select_previous(),
+            _ => {}
+        }
     }
 
     fn select_previous(&mut self) {
-        if self.devices.is_empty() {
+        if self.rows.is_empty() {
             self.table_state.select(None);
             self.selected_id = None;
             return;
         }
         let next = match self.table_state.selected() {
             Some(index) if index > 0 => index - 1,
-            Some(_) | None => self.devices.len() - 1,
+            Some(_) | None => self.rows.len() - 1,
         };
-        self.table_state.select(Some(next));
-        self.selected_id = self.devices.get(next).map(|device| device.id.clone());
+        self.select_index(next);
     }
 }
 
+fn export_path(stem: &str, extension: &str) -> PathBuf {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    PathBuf::from(format!("{stem}-{timestamp}.{extension}"))
+}
+
 fn main() -> Result<()> {
+    let cli = Cli::parse();
+    if cli.list_adapters {
+        return list_adapters();
+    }
+    let mut config: Config = store::load_config().context("load config")?;
+    config.validate().context("load config")?;
+    let legacy_keys = store::load_victron_keys();
+    if !legacy_keys.is_empty() {
+        eprintln!(
+            "victron_keys.json is deprecated; move its keys to [victron_keys] in config.toml"
+        );
+        for (address, key) in legacy_keys {
+            config.victron_keys.entry(address).or_insert(key);
+        }
+    }
+    if cli.pcap.is_some() && cli.replay.is_none() {
+        // A pcap needs the advertising PDUs as sent, and btleplug only reports parsed fields.
+        bail!(
+            "--pcap needs raw advertising data, which live scans don't provide; use it with --replay"
+        );
+    }
+    if cli.no_tui {
+        return run_headless(&cli, &config);
+    }
+
+    let theme = config.theme.build().context("load theme")?;
+
+    install_panic_hook();
+
     let mut stdout = io::stdout();
     crossterm::terminal::enable_raw_mode().context("enable raw mode")?;
-    execute!(stdout, EnterAlternateScreen).context("enter alternate screen")?;
+    execute!(stdout, EnterAlternateScreen, EnableMouseCapture).context("enter alternate screen")?;
 
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend).context("create terminal")?;
 
-    let result = run_app(&mut terminal);
+    let result = run_app(&mut terminal, &cli, &config, &theme);
 
     crossterm::terminal::disable_raw_mode().context("disable raw mode")?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen).context("leave alternate screen")?;
+    execute!(
+        terminal.backend_mut(),
+        LeaveAlternateScreen,
+        DisableMouseCapture
+    )
+    .context("leave alternate screen")?;
     terminal.show_cursor().context("show cursor")?;
 
     result
 }
 
-fn run_app(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> Result<()> {
+// Restore the terminal before the default hook prints, otherwise the panic message lands
+// on the alternate screen in raw mode and the shell is left unusable.
+fn install_panic_hook() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        // Runtime worker panics don't take down the UI, so only the main thread restores.
+        if std::thread::current().name() == Some("main") {
+            let _ = crossterm::terminal::disable_raw_mode();
+            let _ = execute!(
+                io::stdout(),
+                LeaveAlternateScreen,
+                DisableMouseCapture,
+                crossterm::cursor::Show
+            );
+        }
+        default_hook(info);
+    }));
+}
+
+fn list_adapters() -> Result<()> {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_time()
+        .build()
+        .context("build tokio runtime")?;
+    let adapters = runtime
+        .block_on(bleah::list_adapters())
+        .map_err(anyhow::Error::msg)?;
+    for (index, info) in adapters.iter().enumerate() {
+        println!("{index}: {info}");
+    }
+    Ok(())
+}
+
+fn run_app(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    cli: &Cli,
+    config: &Config,
+    theme: &Theme,
+) -> Result<()> {
     let (tx, rx) = mpsc::channel::<ScanMessage>();
+    let (command_tx, command_rx) = tokio_mpsc::unbounded_channel();
     let (shutdown_tx, shutdown_rx) = watch::channel(false);
 
     let runtime = tokio::runtime::Builder::new_multi_thread()
         .enable_time()
         .build()
         .context("build tokio runtime")?;
-    runtime.spawn(bleah::scan_loop(tx, shutdown_rx));
+    let min_rssi = cli.scan_config(config).min_rssi;
+    let scan = spawn_source(&runtime, cli, config, tx, command_rx, shutdown_rx)?;
+
+    let decoders = decoders(cli, config)?;
+    let mut sinks = sinks(cli, config, runtime.handle(), &decoders)?;
 
     let mut state = AppState::new();
-    let decoders = bleah::default_decoders();
+    state.filters.hide_unknown = cli.hide_unknown(config);
+    state.filters.min_rssi = min_rssi;
+    state.rssi_meter = config.rssi_meter();
+    state.reference_power = config
+        .reference_power
+        .unwrap_or(bleah::DEFAULT_REFERENCE_POWER);
+    let mut alerts = alert_watcher(cli, config);
     let tick_rate = Duration::from_millis(250);
+    let deadline = cli.duration.map(|duration| Instant::now() + duration);
 
     loop {
+        if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            break;
+        }
+
         while let Ok(msg) = rx.try_recv() {
-            state.apply(msg);
+            for sink in &mut sinks {
+                sink.handle(&msg);
+            }
+            let raised = match &msg {
+                ScanMessage::Devices(devices) => alerts.check(devices),
+                _ => Vec::new(),
+            };
+            state.apply(msg, &decoders);
+            if !raised.is_empty() {
+                ring_bell();
+            }
+            for alert in raised {
+                state.raise_alert(alert);
+            }
         }
+        for sink in &mut sinks {
+            if let Some(status) = sink.take_status() {
+                state.status = status;
+            }
+        }
+        state.release_connection(&command_tx);
 
-        terminal.draw(|frame| draw_ui(frame, &mut state, &decoders))?;
+        terminal.draw(|frame| draw_ui(frame, &mut state, &decoders, theme))?;
 
         if event::poll(tick_rate)? {
-            if let Event::Key(key) = event::read()? {
-                match key.code {
-                    KeyCode::Char('q') | KeyCode::Esc => break,
+            let key = match event::read()? {
+                Event::Key(key) => key,
+                Event::Mouse(mouse) => {
+                    state.handle_mouse(mouse);
+                    continue;
+                }
+                _ => continue,
+            };
+            match state.input_mode {
+                InputMode::Search => match key.code {
+                    KeyCode::Esc => state.clear_filter(),
+                    KeyCode::Enter => state.input_mode = InputMode::Normal,
+                    KeyCode::Backspace => state.pop_filter_char(),
+                    KeyCode::Char(c) => state.push_filter_char(c),
                     KeyCode::Down => state.select_next(),
                     KeyCode::Up => state.select_previous(),
                     _ => {}
+                },
+                InputMode::Nickname => match key.code {
+                    KeyCode::Esc => state.cancel_nickname(),
+                    KeyCode::Enter => state.commit_nickname(),
+                    KeyCode::Backspace => {
+                        state.nickname_input.pop();
+                    }
+                    KeyCode::Char(c) => state.nickname_input.push(c),
+                    _ => {}
+                },
+                InputMode::Help => {
+                    if matches!(key.code, KeyCode::Char('?') | KeyCode::Esc) {
+                        state.input_mode = InputMode::Normal;
+                    }
+                }
+                InputMode::Gatt => match key.code {
+                    KeyCode::Esc | KeyCode::Char('b') => state.input_mode = InputMode::Normal,
+                    KeyCode::Down | KeyCode::Char('j') => state.select_next_characteristic(),
+                    KeyCode::Up | KeyCode::Char('k') => state.select_previous_characteristic(),
+                    KeyCode::Enter | KeyCode::Char('r') => {
+                        state.read_selected_characteristic(&command_tx)
+                    }
+                    KeyCode::Char('w') => state.start_characteristic_write(),
+                    _ => {}
+                },
+                InputMode::GattWrite => match key.code {
+                    KeyCode::Esc => state.cancel_characteristic_write(),
+                    KeyCode::Enter => state.commit_characteristic_write(&command_tx),
+                    KeyCode::Backspace => {
+                        state.write_input.pop();
+                    }
+                    KeyCode::Char(c) => state.write_input.push(c),
+                    _ => {}
+                },
+                InputMode::Normal => match key.code {
+                    KeyCode::Esc if !state.filters.search.is_empty() => state.clear_filter(),
+                    KeyCode::Char('q') | KeyCode::Esc => break,
+                    KeyCode::Down | KeyCode::Char('j') => state.select_next(),
+                    KeyCode::Up | KeyCode::Char('k') => state.select_previous(),
+                    KeyCode::Char('g') => state.select_first(),
+                    KeyCode::Char('G') => state.select_last(),
+                    KeyCode::Enter => state.activate_selected(&command_tx),
+                    KeyCode::Right => state.set_group_expanded(Some(true)),
+                    KeyCode::Left => state.set_group_expanded(Some(false)),
+                    KeyCode::Char('v') => state.toggle_grouped(),
+                    KeyCode::Char('s') => state.cycle_sort(),
+                    KeyCode::Char('S') => state.reverse_sort(),
+                    KeyCode::Char('/') => state.input_mode = InputMode::Search,
+                    KeyCode::Char('?') => state.input_mode = InputMode::Help,
+                    KeyCode::Char('u') => state.toggle_hide_unknown(),
+                    KeyCode::Char('o') => state.toggle_connected_only(),
+                    KeyCode::Char('b') => state.start_gatt_browser(),
+                    KeyCode::Char('d') => state.toggle_verbose_details(),
+                    KeyCode::Char('n') => state.start_nickname(),
+                    KeyCode::Char('f') => state.toggle_pin(),
+                    KeyCode::Char('p') => state.toggle_pause(),
+                    KeyCode::Char('y') => state.copy_selected_address(),
+                    KeyCode::Char('Y') => state.copy_selected_json(&decoders),
+                    KeyCode::Char('e') => state.export_json(),
+                    KeyCode::Char('E') => state.export_selected_json(&decoders),
+                    KeyCode::Char('c') => state.export_csv(&decoders),
+                    KeyCode::PageDown if key.modifiers.contains(KeyModifiers::SHIFT) => {
+                        state.scroll_details_down(10)
+                    }
+                    KeyCode::PageUp if key.modifiers.contains(KeyModifiers::SHIFT) => {
+                        state.scroll_details_up(10)
+                    }
+                    KeyCode::PageDown => state.select_page_down(),
+                    KeyCode::PageUp => state.select_page_up(),
+                    KeyCode::Char('J') => state.scroll_details_down(1),
+                    KeyCode::Char('K') => state.scroll_details_up(1),
+                    _ => {}
+                },
+            }
+        }
+    }
+
+    let _ = shutdown_tx.send(true);
+    // Give scan_loop a moment to disconnect any GATT connections before tearing down.
+    let _ = runtime.block_on(tokio::time::timeout(Duration::from_secs(2), scan));
+    runtime.shutdown_timeout(Duration::from_secs(1));
+
+    // The TUI is still up here, so final write errors are returned for main to print once
+    // the terminal is restored.
+    let mut errors = Vec::new();
+    for sink in &mut sinks {
+        sink.flush();
+        errors.extend(sink.take_status());
+    }
+    if !errors.is_empty() {
+        bail!(errors.join("\n"));
+    }
+
+    Ok(())
+}
+
+fn run_headless(cli: &Cli, config: &Config) -> Result<()> {
+    let (tx, rx) = mpsc::channel::<ScanMessage>();
+    let (_command_tx, command_rx) = tokio_mpsc::unbounded_channel();
+    let (shutdown_tx, shutdown_rx) = watch::channel(false);
+
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_time()
+        .build()
+        .context("build tokio runtime")?;
+    spawn_source(&runtime, cli, config, tx, command_rx, shutdown_rx)?;
+
+    let decoders = decoders(cli, config)?;
+    let mut sinks = sinks(cli, config, runtime.handle(), &decoders)?;
+    let mut alerts = alert_watcher(cli, config);
+    let deadline = cli.duration.map(|duration| Instant::now() + duration);
+
+    loop {
+        let msg = match deadline {
+            Some(deadline) => {
+                match rx.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+                    Ok(msg) => msg,
+                    Err(_) => break,
                 }
             }
+            None => match rx.recv() {
+                Ok(msg) => msg,
+                Err(_) => break,
+            },
+        };
+        for sink in &mut sinks {
+            sink.handle(&msg);
+            if let Some(status) = sink.take_status() {
+                eprintln!("{status}");
+            }
+        }
+        if sinks.iter().any(|sink| sink.finished()) {
+            break;
+        }
+        match msg {
+            ScanMessage::Status(status) => eprintln!("{status}"),
+            ScanMessage::Devices(devices) => {
+                for alert in alerts.check(&devices) {
+                    eprintln!("{}", alert.message);
+                }
+            }
+            _ => {}
         }
     }
 
     let _ = shutdown_tx.send(true);
     runtime.shutdown_timeout(Duration::from_secs(1));
 
+    for sink in &mut sinks {
+        sink.flush();
+        if let Some(status) = sink.take_status() {
+            eprintln!("{status}");
+        }
+    }
+
     Ok(())
 }
 
-fn draw_ui(frame: &mut Frame, state: &mut AppState, decoders: &[Box<dyn PeripheralDecoder>]) {
+fn alert_watcher(cli: &Cli, config: &Config) -> AlertWatcher {
+    let targets = cli.alerts.iter().chain(&config.alerts).cloned().collect();
+    AlertWatcher::new(targets, cli.alert_rssi.or(config.alert_rssi))
+}
+
+fn ring_bell() {
+    let mut stdout = io::stdout();
+    let _ = stdout.write_all(b"\x07").and_then(|()| stdout.flush());
+}
+
+// Either the radio or a recording feeds the scan channel; the consumers can't tell.
+fn spawn_source(
+    runtime: &tokio::runtime::Runtime,
+    cli: &Cli,
+    config: &Config,
+    tx: mpsc::Sender<ScanMessage>,
+    commands: tokio_mpsc::UnboundedReceiver<ScanCommand>,
+    shutdown: watch::Receiver<bool>,
+) -> Result<tokio::task::JoinHandle<()>> {
+    let Some(path) = &cli.replay else {
+        let scan_config = cli.scan_config(config);
+        return Ok(runtime.spawn(bleah::scan_loop(scan_config, tx, commands, shutdown)));
+    };
+    let snapshots = bleah::replay::load(path)
+        .with_context(|| format!("read replay file {}", path.display()))?;
+    let replay_config = cli.replay_config();
+    Ok(runtime.spawn(bleah::replay::replay_loop(
+        snapshots,
+        replay_config,
+        tx,
+        commands,
+        shutdown,
+    )))
+}
+
+// Every enabled output consumes the same scan messages; the TUI is just one more reader.
+fn sinks(
+    cli: &Cli,
+    config: &Config,
+    runtime: &tokio::runtime::Handle,
+    decoders: &SharedDecoders,
+) -> Result<Vec<Box<dyn ScanSink>>> {
+    let mut sinks: Vec<Box<dyn ScanSink>> = Vec::new();
+    if cli.ndjson {
+        sinks.push(Box::new(NdjsonWriter::new(decoders.clone())));
+    }
+    if let Some(path) = &cli.json {
+        sinks.push(Box::new(JsonSnapshot::new(path.clone())));
+    }
+    if let Some(path) = &cli.csv {
+        sinks.push(Box::new(CsvSnapshot::new(path.clone(), decoders.clone())));
+    }
+    if let Some(path) = &cli.pcap {
+        let pcap = PcapWriter::create(path).context("create pcap file")?;
+        sinks.push(Box::new(pcap));
+    }
+    if let Some(target) = &cli.influx {
+        let target = InfluxTarget::parse(target).map_err(anyhow::Error::msg)?;
+        let influx = InfluxSink::spawn(runtime, target, decoders.clone());
+        sinks.push(Box::new(influx));
+    }
+    if let Some(log) = config.log.build()? {
+        sinks.push(Box::new(RollingLog::spawn(runtime, log, decoders.clone())));
+    }
+    #[cfg(feature = "metrics")]
+    if let Some(address) = cli.metrics {
+        let server = bleah::metrics::MetricsServer::spawn(runtime, address, decoders.clone())
+            .with_context(|| format!("listen for metrics on {address}"))?;
+        sinks.push(Box::new(server));
+    }
+    #[cfg(feature = "sqlite")]
+    if let Some(path) = &cli.sqlite {
+        let sqlite =
+            bleah::sqlite::SqliteSink::spawn(runtime, path).context("open SQLite database")?;
+        sinks.push(Box::new(sqlite));
+    }
+    #[cfg(feature = "mqtt")]
+    if let Some(config) = cli.mqtt_config()? {
+        let mqtt = bleah::mqtt::MqttSink::spawn(runtime, config, decoders.clone());
+        sinks.push(Box::new(mqtt));
+    }
+    Ok(sinks)
+}
+
+fn decoders(cli: &Cli, config: &Config) -> Result<SharedDecoders> {
+    let mut victron_keys = HashMap::new();
+    for (address, key) in &config.victron_keys {
+        let key = parse_key(key).with_context(|| format!("invalid Victron key for {address}"))?;
+        victron_keys.insert(address.clone(), key);
+    }
+    let mut registry = DecoderRegistry::with_victron_keys(victron_keys);
+    for name in config.disabled_decoders.iter().chain(&cli.disable_decoders) {
+        if !registry.set_enabled(name, false) {
+            let known = registry.names().map(|(name, _)| name).collect::<Vec<_>>();
+            bail!("unknown decoder {name:?} (known: {})", known.join(", "));
+        }
+    }
+    Ok(registry.build().into())
+}
+
+fn parse_key(hex: &str) -> Result<[u8; 16]> {
+    let bytes = bleah::parse_hex_bytes(hex).map_err(anyhow::Error::msg)?;
+    let len = bytes.len();
+    bytes
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("expected 16 bytes, got {len}"))
+}
+
+fn draw_ui(
+    frame: &mut Frame,
+    state: &mut AppState,
+    decoders: &[Box<dyn PeripheralDecoder>],
+    theme: &Theme,
+) {
     let layout = Layout::default()
         .direction(Direction::Vertical)
         .margin(1)
         .constraints([
+            Constraint::Length(1),
             Constraint::Length(1),
             Constraint::Min(5),
             Constraint::Length(1),
         ])
         .split(frame.size());
 
-    let title = Line::from(vec![
+    let mut title_spans = vec![
         Span::styled("bleah", Style::default().add_modifier(Modifier::BOLD)),
         Span::raw(" - BLE devices "),
         Span::styled(
-            format!("({})", state.devices.len()),
+            format!("({}/{})", state.devices.len(), state.all_devices.len()),
             Style::default().add_modifier(Modifier::DIM),
         ),
         Span::raw(" "),
         Span::styled(
             state.status.clone(),
-            Style::default().fg(Color::Yellow),
+            Style::default().fg(theme.status),
         ),
-    ]);
+    ];
+    if state.paused {
+        title_spans.push(Span::raw(" "));
+        title_spans.push(Span::styled(
+            "PAUSED",
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        ));
+    }
+    if state.filters.connected_only {
+        title_spans.push(Span::raw(" "));
+        title_spans.push(Span::styled("CONNECTED", Style::default().fg(theme.accent)));
+    }
+    if state.input_mode == InputMode::Search || !state.filters.search.is_empty() {
+        let cursor = if state.input_mode == InputMode::Search {
+            "_"
+        } else {
+            ""
+        };
+        title_spans.push(Span::raw(" "));
+        title_spans.push(Span::styled(
+            format!("/{}{cursor}", state.filters.search),
+            Style::default().fg(theme.accent),
+        ));
+    }
+    if state.input_mode == InputMode::Nickname {
+        title_spans.push(Span::raw(" "));
+        title_spans.push(Span::styled(
+            format!("Nickname: {}_", state.nickname_input),
+            Style::default().fg(theme.accent),
+        ));
+    }
+    let title = Line::from(title_spans);
     frame.render_widget(Paragraph::new(title), layout[0]);
+    frame.render_widget(stats_line(state), layout[1]);
 
     let content = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([Constraint::Percentage(55), Constraint::Percentage(45)])
-        .split(layout[1]);
+        .split(layout[2]);
 
-    let header = Row::new(vec![
-        Cell::from("Address"),
-        Cell::from("Name"),
-        Cell::from("RSSI"),
-        Cell::from("Connected"),
-    ])
-    .style(Style::default().add_modifier(Modifier::BOLD));
+    let sort_column = state.sort_key.column();
+    let header = TABLE_COLUMNS
+        .into_iter()
+        .enumerate()
+        .map(|(column, title)| {
+            if column == sort_column {
+                Cell::from(format!("{title} {}", state.sort_arrow()))
+            } else {
+                Cell::from(title)
+            }
+        });
+    let header = Row::new(header).style(theme.header);
 
-    let rows = state.devices.iter().map(|device| {
+    let device_row = |device: &DeviceInfo| {
         let summary = device_summary(device, decoders);
-        let mut name_spans = vec![Span::styled(
-            device.name.clone(),
+        let mut name_spans = Vec::new();
+        if state.pinned.contains(device.address()) {
+            name_spans.push(Span::styled("* ", Style::default().fg(theme.status)));
+        }
+        name_spans.push(Span::styled(
+            state.display_name(device).to_string(),
             Style::default().add_modifier(Modifier::BOLD),
-        )];
+        ));
         if let Some(extra) = summary {
             name_spans.push(Span::raw(" "));
             name_spans.push(Span::raw(extra));
         }
         let rssi = device
-            .rssi
+            .rssi_smoothed
             .map(|value| value.to_string())
             .unwrap_or_else(|| "-".to_string());
+        let rssi_color = theme.rssi_color(device.rssi_smoothed);
+        let meter = rssi_bar(device.rssi_smoothed, state.rssi_meter, RSSI_METER_WIDTH);
         let connected = if device.connected { "yes" } else { "no" };
+        let age = bleah::format_age(device.last_seen.elapsed().unwrap_or_default());
+        let address_tag = match device.address_type {
+            _ if state.rotating.contains(&device.id) => {
+                Span::styled(" ↻", Style::default().fg(theme.accent))
+            }
+            Some(AddressType::Random) => Span::styled(" R", Style::default().fg(theme.status)),
+            Some(AddressType::Public) => Span::raw(" P"),
+            None => Span::raw(""),
+        };
+        let glyph = device_glyph(device, decoders);
+        // Possible unknown trackers are a safety concern, so make them stand out.
+        let glyph_style = if glyph == TRACKER_GLYPH {
+            Style::default()
+                .fg(theme.tracker)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+        };
+        let row_style = if state.flash_on(&device.id) {
+            Style::default()
+                .fg(theme.alert)
+                .add_modifier(Modifier::BOLD | Modifier::REVERSED)
+        } else {
+            Style::default()
+        };
         Row::new(vec![
-            Cell::from(device.id.clone()),
+            Cell::from(glyph.to_string()).style(glyph_style),
+            Cell::from(Line::from(vec![Span::raw(device.id.clone()), address_tag])),
             Cell::from(Line::from(name_spans)),
-            Cell::from(rssi),
+            Cell::from(vendor_label(device)),
+            Cell::from(rssi).style(Style::default().fg(rssi_color)),
+            Cell::from(meter).style(Style::default().fg(rssi_color)),
+            Cell::from(age),
             Cell::from(connected),
         ])
+        .style(row_style)
+    };
+    let rows = state.rows.iter().map(|row| match *row {
+        ListRow::Group { key, count } => {
+            let marker = if state.expanded.contains(&key) {
+                '▾'
+            } else {
+                '▸'
+            };
+            let vendor = match key {
+                Some(company_id) => bleah::company_name(company_id)
+                    .map(str::to_string)
+                    .unwrap_or_else(|| format!("0x{company_id:04X}")),
+                None => "No manufacturer data".to_string(),
+            };
+            Row::new(vec![
+                Cell::from(marker.to_string()),
+                Cell::from(""),
+                Cell::from(Line::from(vec![
+                    Span::styled(vendor, Style::default().add_modifier(Modifier::BOLD)),
+                    Span::styled(format!(" ({count})"), Style::default().fg(theme.accent)),
+                ])),
+            ])
+        }
+        ListRow::Device(index) => device_row(&state.devices[index]),
     });
 
     let table = Table::new(
         rows,
         [
-            Constraint::Length(18),
+            Constraint::Length(1),
+            Constraint::Length(20),
             Constraint::Min(10),
+            Constraint::Length(VENDOR_WIDTH as u16),
+            Constraint::Length(6),
+            Constraint::Length(RSSI_METER_WIDTH as u16),
             Constraint::Length(6),
             Constraint::Length(10),
         ],
@@ -218,15 +1566,143 @@ fn draw_ui(frame: &mut Frame, state: &mut AppState, decoders: &[Box<dyn Peripher
     .header(header)
     .block(Block::default().title("Nearby devices").borders(Borders::ALL))
     .column_spacing(1)
-    .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    .highlight_style(theme.highlight);
 
     frame.render_stateful_widget(table, content[0], &mut state.table_state);
+    state.table_area = content[0];
+
+    let side = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(5), Constraint::Length(5)])
+        .split(content[1]);
+
+    let (lines, focus) = details_lines(state, decoders);
+    let visible_height = side[0].height.saturating_sub(2);
+    // Long values wrap, so scroll over rendered rows rather than lines.
+    let inner_width = side[0].width.saturating_sub(2);
+    // Keep the browser cursor on screen.
+    if let Some(focus) = focus {
+        let top = wrapped_height(&lines[..focus], inner_width);
+        let bottom = wrapped_height(&lines[..=focus], inner_width);
+        if top < state.details_scroll {
+            state.details_scroll = top;
+        } else if bottom > state.details_scroll + visible_height {
+            state.details_scroll = bottom.saturating_sub(visible_height.max(1));
+        }
+    }
+    let max_scroll = wrapped_height(&lines, inner_width).saturating_sub(visible_height);
+    state.details_scroll = state.details_scroll.min(max_scroll);
+    let details = details_panel(lines, state.details_scroll);
+    frame.render_widget(details, side[0]);
+    state.details_area = side[0];
+
+    let history = rssi_sparkline_data(state);
+    let sparkline = Sparkline::default()
+        .block(Block::default().title("RSSI history").borders(Borders::ALL))
+        .data(&history)
+        .max(100)
+        .style(Style::default().fg(theme.accent));
+    frame.render_widget(sparkline, side[1]);
+
+    let help = if state.input_mode == InputMode::Gatt {
+        Paragraph::new("up/down to pick a characteristic, r/enter read, w write, esc to leave")
+    } else if state.input_mode == InputMode::GattWrite {
+        Paragraph::new(Line::from(vec![
+            Span::raw("Write hex: "),
+            Span::styled(
+                format!("{}_", state.write_input),
+                Style::default().fg(theme.accent),
+            ),
+            Span::raw("  (enter to send, esc to cancel)"),
+        ]))
+    } else {
+        Paragraph::new(format!(
+            "? for help, / to search, s/S to sort (by {} {}), q/esc to quit",
+            state.sort_key.label(),
+            state.sort_arrow()
+        ))
+    };
+    frame.render_widget(help, layout[3]);
+
+    if state.input_mode == InputMode::Help {
+        let area = centered_rect(60, 70, frame.size());
+        frame.render_widget(Clear, area);
+        frame.render_widget(help_popup(theme), area);
+    }
+}
+
+fn help_popup(theme: &Theme) -> Paragraph<'static> {
+    let mut lines = Vec::new();
+    for (category, bindings) in KEYBINDINGS {
+        if !lines.is_empty() {
+            lines.push(Line::from(""));
+        }
+        lines.push(Line::from(Span::styled(
+            *category,
+            Style::default().add_modifier(Modifier::BOLD),
+        )));
+        for (keys, action) in *bindings {
+            lines.push(Line::from(vec![
+                Span::styled(format!("  {keys:<16}"), Style::default().fg(theme.accent)),
+                Span::raw(*action),
+            ]));
+        }
+    }
+    Paragraph::new(lines)
+        .block(Block::default().title("Help").borders(Borders::ALL))
+        .wrap(Wrap { trim: false })
+}
 
-    let details = details_panel(state.selected_device(), decoders);
-    frame.render_widget(details, content[1]);
+fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
+    let vertical = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage((100 - percent_y) / 2),
+            Constraint::Percentage(percent_y),
+            Constraint::Percentage((100 - percent_y) / 2),
+        ])
+        .split(area);
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage((100 - percent_x) / 2),
+            Constraint::Percentage(percent_x),
+            Constraint::Percentage((100 - percent_x) / 2),
+        ])
+        .split(vertical[1])[1]
+}
 
-    let help = Paragraph::new("up/down to select, q/esc to quit");
-    frame.render_widget(help, layout[2]);
+fn stats_line(state: &AppState) -> Paragraph<'static> {
+    let last_poll = match state.last_poll {
+        Some(at) => format!("{:.1}s ago", at.elapsed().as_secs_f32()),
+        None => "never".to_string(),
+    };
+    Paragraph::new(format!(
+        "Seen: {}  Visible: {}  Decoded: {}  Last poll: {last_poll}",
+        state.seen_ids.len(),
+        state.devices.len(),
+        state.decoded_count
+    ))
+    .style(Style::default().add_modifier(Modifier::DIM))
+}
+
+fn area_contains(area: Rect, column: u16, row: u16) -> bool {
+    column >= area.x && column < area.right() && row >= area.y && row < area.bottom()
+}
+
+fn rssi_sparkline_data(state: &AppState) -> Vec<u64> {
+    let Some(history) = state
+        .selected_device()
+        .and_then(|device| state.rssi_history.get(&device.id))
+    else {
+        return Vec::new();
+    };
+    // Map -100..0 dBm onto 0..100 so stronger signals draw taller bars.
+    history
+        .samples
+        .iter()
+        .map(|rssi| (i64::from(*rssi) + 100).clamp(0, 100) as u64)
+        .collect()
 }
 
 fn device_summary(
@@ -236,22 +1712,108 @@ fn device_summary(
     decoders.iter().find_map(|decoder| decoder.summary(device))
 }
 
-fn details_panel(
-    device: Option<&DeviceInfo>,
+// Lowest company id wins when a device carries several manufacturer data entries.
+fn vendor_label(device: &DeviceInfo) -> String {
+    let Some(company_id) = device.manufacturer_data.keys().next() else {
+        return String::new();
+    };
+    let name = match bleah::company_name(*company_id) {
+        Some(name) => name.to_string(),
+        None => format!("0x{company_id:04X}"),
+    };
+    if name.chars().count() > VENDOR_WIDTH {
+        let truncated: String = name.chars().take(VENDOR_WIDTH - 1).collect();
+        format!("{truncated}…")
+    } else {
+        name
+    }
+}
+
+// Eighth-block glyphs give the bar sub-cell resolution; unknown RSSI renders as an empty bar.
+fn rssi_bar(rssi: Option<i16>, (floor, ceiling): (i16, i16), width: usize) -> String {
+    const EIGHTHS: [char; 8] = [' ', '▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+    let Some(rssi) = rssi.filter(|_| ceiling > floor) else {
+        return " ".repeat(width);
+    };
+    let fraction = f64::from(rssi.clamp(floor, ceiling) - floor) / f64::from(ceiling - floor);
+    let eighths = (fraction * (width * 8) as f64).round() as usize;
+    let mut bar = "█".repeat(eighths / 8);
+    if eighths / 8 < width {
+        bar.push(EIGHTHS[eighths % 8]);
+    }
+    format!("{bar:<width$}")
+}
+
+// Single-width symbols only; emoji render double-width or not at all in many terminals.
+fn device_glyph(device: &DeviceInfo, decoders: &[Box<dyn PeripheralDecoder>]) -> char {
+    // The appearance category lives in the upper ten bits.
+    let from_appearance = match device.appearance.map(|appearance| appearance >> 6) {
+        Some(0x01) => Some('☎'),
+        Some(0x02) => Some('▣'),
+        Some(0x03) => Some('◷'),
+        Some(0x08 | 0x09) => Some('◎'),
+        Some(0x0c | 0x15) => Some('°'),
+        Some(0x0d) => Some('♥'),
+        Some(0x0f) => Some('⌨'),
+        Some(0x1e) => Some('↯'),
+        Some(0x21 | 0x22 | 0x25) => Some('♫'),
+        _ => None,
+    };
+    let from_decoder = || {
+        decoders
+            .iter()
+            .filter(|decoder| decoder.summary(device).is_some())
+            .find_map(|decoder| decoder.category())
+            .map(|category| match category {
+                DeviceCategory::Sensor => '°',
+                DeviceCategory::Beacon => '◎',
+                DeviceCategory::Phone => '☎',
+                DeviceCategory::Input => '⌨',
+                DeviceCategory::Energy => '↯',
+                DeviceCategory::HeartRate => '♥',
+                DeviceCategory::Tracker => TRACKER_GLYPH,
+            })
+    };
+    from_appearance.or_else(from_decoder).unwrap_or('·')
+}
+
+// Also returns the line of the characteristic under the browser cursor, if browsing.
+fn details_lines(
+    state: &AppState,
     decoders: &[Box<dyn PeripheralDecoder>],
-) -> Paragraph<'static> {
-    let lines = match device {
-        Some(device) => device_details(device, decoders),
-        None => vec![Line::from("No device selected.")],
+) -> (Vec<Line<'static>>, Option<usize>) {
+    let Some(device) = state.selected_device() else {
+        return (vec![Line::from("No device selected.")], None);
     };
+    let mut lines = device_details(device, state, decoders);
+    let mut focus = None;
+    if let Some(gatt) = state.selected_gatt() {
+        let browsing = matches!(state.input_mode, InputMode::Gatt | InputMode::GattWrite);
+        let cursor = browsing.then_some(state.gatt_cursor);
+        let (gatt_lines, gatt_focus) = gatt_details(gatt, cursor, &state.gatt_values);
+        focus = gatt_focus.map(|line| lines.len() + line);
+        lines.extend(gatt_lines);
+    }
+    (lines, focus)
+}
+
+fn wrapped_height(lines: &[Line<'static>], width: u16) -> u16 {
+    let rows = Paragraph::new(lines.to_vec())
+        .wrap(Wrap { trim: false })
+        .line_count(width);
+    u16::try_from(rows).unwrap_or(u16::MAX)
+}
 
+fn details_panel(lines: Vec<Line<'static>>, scroll: u16) -> Paragraph<'static> {
     Paragraph::new(lines)
         .block(Block::default().title("Device details").borders(Borders::ALL))
         .wrap(Wrap { trim: false })
+        .scroll((scroll, 0))
 }
 
 fn device_details(
     device: &DeviceInfo,
+    state: &AppState,
     decoders: &[Box<dyn PeripheralDecoder>],
 ) -> Vec<Line<'static>> {
     let mut lines = Vec::new();
@@ -260,11 +1822,7 @@ fn device_details(
         device.name.clone(),
         Style::default().add_modifier(Modifier::BOLD),
     )]));
-    lines.push(Line::from(format!("Address: {}", device.id)));
-    lines.push(Line::from(format!(
-        "Connected: {}",
-        if device.connected { "yes" } else { "no" }
-    )));
+    lines.push(Line::from(format!("Address: {}", device.address())));
     lines.push(Line::from(format!(
         "RSSI: {}",
         device
@@ -272,16 +1830,80 @@ fn device_details(
             .map(|value| value.to_string())
             .unwrap_or_else(|| "-".to_string())
     )));
+    if !state.verbose_details {
+        let reading = match bleah::summary_with_source(decoders, device) {
+            Some((source, summary)) => format!("{summary} ({source})"),
+            None => "-".to_string(),
+        };
+        lines.push(Line::from(format!("Reading: {reading}")));
+        lines.push(Line::from(Span::styled(
+            "d for all fields and raw data",
+            Style::default().add_modifier(Modifier::DIM),
+        )));
+        return lines;
+    }
+    lines.push(Line::from(format!(
+        "Connected: {}",
+        if device.connected { "yes" } else { "no" }
+    )));
+    if let Some(smoothed) = device.rssi_smoothed {
+        lines.push(Line::from(format!("RSSI (smoothed): {smoothed}")));
+    }
+    if let Some(distance) = bleah::estimate_distance(device, state.reference_power) {
+        lines.push(Line::from(format!("Est. distance: {distance:.1} m")));
+    }
+    let last_seen = device.last_seen.elapsed().unwrap_or_default();
+    lines.push(Line::from(format!(
+        "Last seen: {}s ago",
+        last_seen.as_secs()
+    )));
+    let tracked = device
+        .first_seen
+        .elapsed()
+        .unwrap_or_default()
+        .as_secs_f64();
+    let rate = if tracked >= 1.0 {
+        format!(" ({:.1}/min)", f64::from(device.adv_count) * 60.0 / tracked)
+    } else {
+        String::new()
+    };
+    lines.push(Line::from(format!(
+        "Advertisements: {}{rate}",
+        device.adv_count
+    )));
     if let Some(tx_power) = device.tx_power_level {
         lines.push(Line::from(format!("Tx power: {tx_power}")));
     }
     if let Some(address_type) = device.address_type {
-        lines.push(Line::from(format!("Address type: {address_type:?}")));
+        let mut spans = vec![Span::raw(format!("Address type: {address_type:?}"))];
+        if state.rotating.contains(&device.id) {
+            spans.push(Span::styled(
+                " (rotating; likely seen before under another address)",
+                Style::default().add_modifier(Modifier::DIM),
+            ));
+        }
+        lines.push(Line::from(spans));
+    }
+    if let Some(appearance) = device.appearance {
+        let label = match bleah::appearance_name(appearance) {
+            Some(name) => name.to_string(),
+            None => format!("0x{appearance:04X}"),
+        };
+        lines.push(Line::from(format!("Appearance: {label}")));
+    }
+    if let Some(connectable) = device.connectable {
+        let label = if connectable { "yes" } else { "no" };
+        lines.push(Line::from(format!("Connectable: {label}")));
     }
     if device.services.is_empty() {
         lines.push(Line::from("Services: -"));
     } else {
-        lines.push(Line::from(format!("Services: {}", device.services.join(", "))));
+        let services = device
+            .services
+            .iter()
+            .map(|uuid| service_label(uuid))
+            .collect::<Vec<_>>();
+        lines.push(Line::from(format!("Services: {}", services.join(", "))));
     }
 
     let decoded = decoded_details(device, decoders);
@@ -291,8 +1913,14 @@ fn device_details(
             "Decoded data",
             Style::default().add_modifier(Modifier::BOLD),
         )]));
-        for item in decoded {
-            lines.push(Line::from(format!("{}: {}", item.label, item.value)));
+        for (name, items) in decoded {
+            lines.push(Line::from(Span::styled(
+                name.to_string(),
+                Style::default().add_modifier(Modifier::UNDERLINED),
+            )));
+            for item in items {
+                lines.push(Line::from(format!("  {}: {}", item.label, item.value)));
+            }
         }
     }
 
@@ -305,10 +1933,12 @@ fn device_details(
         lines.push(Line::from("-"));
     } else {
         for (company_id, data) in &device.manufacturer_data {
-            lines.push(Line::from(format!(
-                "0x{company_id:04x}: {}",
-                bleah::hex_bytes(data)
-            )));
+            let company = match bleah::company_name(*company_id) {
+                Some(name) => format!("{name} (0x{company_id:04x})"),
+                None => format!("0x{company_id:04x}"),
+            };
+            lines.push(Line::from(format!("{company}:")));
+            lines.extend(hex_dump_lines(data));
         }
     }
 
@@ -321,19 +1951,239 @@ fn device_details(
         lines.push(Line::from("-"));
     } else {
         for (uuid, data) in &device.service_data {
-            lines.push(Line::from(format!("{uuid}: {}", bleah::hex_bytes(data))));
+            lines.push(Line::from(format!("{}:", service_label(uuid))));
+            lines.extend(hex_dump_lines(data));
+        }
+    }
+
+    if let Some(raw_adv) = &device.raw_adv {
+        lines.push(Line::from(""));
+        lines.push(Line::from(vec![Span::styled(
+            "Raw advertisement",
+            Style::default().add_modifier(Modifier::BOLD),
+        )]));
+        lines.extend(hex_dump_lines(raw_adv));
+        lines.push(Line::from(vec![Span::styled(
+            "AD structures",
+            Style::default().add_modifier(Modifier::BOLD),
+        )]));
+        for structure in bleah::parse_ad_structures(raw_adv) {
+            lines.push(Line::from(format!(
+                "  {}: {}",
+                structure.label(),
+                structure.value()
+            )));
         }
     }
 
     lines
 }
 
-fn decoded_details(
+fn hex_dump_lines(bytes: &[u8]) -> Vec<Line<'static>> {
+    if bytes.is_empty() {
+        return vec![Line::from("  (empty)")];
+    }
+    bleah::hex_dump(bytes)
+        .lines()
+        .map(|row| Line::from(format!("  {row}")))
+        .collect()
+}
+
+fn gatt_details(
+    gatt: &GattProfile,
+    cursor: Option<usize>,
+    values: &HashMap<(String, String), Result<Vec<u8>, String>>,
+) -> (Vec<Line<'static>>, Option<usize>) {
+    let mut lines = vec![
+        Line::from(""),
+        Line::from(vec![Span::styled(
+            "GATT services",
+            Style::default().add_modifier(Modifier::BOLD),
+        )]),
+    ];
+    for item in gatt.details() {
+        lines.push(Line::from(format!("{}: {}", item.label, item.value)));
+    }
+    if gatt.services.is_empty() {
+        lines.push(Line::from("-"));
+    }
+    let mut focus = None;
+    let mut index = 0;
+    for service in &gatt.services {
+        lines.push(Line::from(service_label(&service.uuid)));
+        for characteristic in &service.characteristics {
+            let properties = characteristic
+                .properties
+                .iter_names()
+                .map(|(name, _)| name.to_lowercase())
+                .collect::<Vec<_>>();
+            let label = format!(
+                "  {} [{}]",
+                characteristic_label(&characteristic.uuid),
+                properties.join(", ")
+            );
+            if cursor == Some(index) {
+                focus = Some(lines.len());
+                let style = Style::default().add_modifier(Modifier::REVERSED);
+                lines.push(Line::from(Span::styled(label, style)));
+            } else {
+                lines.push(Line::from(label));
+            }
+            index += 1;
+            let key = (service.uuid.clone(), characteristic.uuid.clone());
+            match values.get(&key) {
+                Some(Ok(value)) => {
+                    if let Some(decoded) = bleah::decode_characteristic(&characteristic.uuid, value)
+                    {
+                        lines.push(Line::from(format!("    = {decoded}")));
+                    }
+                    lines.extend(hex_dump_lines(value));
+                }
+                Some(Err(err)) => lines.push(Line::from(format!("    read failed: {err}"))),
+                None => {}
+            }
+        }
+    }
+    (lines, focus)
+}
+
+fn characteristic_label(uuid: &str) -> String {
+    match bleah::short_uuid(uuid) {
+        Some(id) => format!("0x{id:04X}"),
+        None => uuid.to_string(),
+    }
+}
+
+fn service_label(uuid: &str) -> String {
+    match (bleah::service_name(uuid), bleah::short_uuid(uuid)) {
+        (Some(name), Some(id)) => format!("{name} (0x{id:04X})"),
+        _ => uuid.to_string(),
+    }
+}
+
+// Decoded items grouped by the decoder that produced them, so overlapping formats
+// (e.g. iBeacon and Tilt) stay distinguishable.
+fn decoded_details<'a>(
     device: &DeviceInfo,
-    decoders: &[Box<dyn PeripheralDecoder>],
-) -> Vec<DetailItem> {
+    decoders: &'a [Box<dyn PeripheralDecoder>],
+) -> Vec<(&'a str, Vec<DetailItem>)> {
     decoders
         .iter()
-        .flat_map(|decoder| decoder.details(device))
+        .map(|decoder| (decoder.name(), decoder.details(device)))
+        .filter(|(_, items)| !items.is_empty())
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+
+    fn device(name: &str, rssi: Option<i16>) -> DeviceInfo {
+        DeviceInfo {
+            id: format!("hci0/dev_{name}"),
+            address: "AA:BB:CC:DD:EE:FF".to_string(),
+            name: name.to_string(),
+            rssi,
+            rssi_smoothed: rssi,
+            connected: false,
+            tx_power_level: None,
+            address_type: None,
+            appearance: None,
+            connectable: None,
+            raw_adv: None,
+            manufacturer_data: BTreeMap::new(),
+            service_data: BTreeMap::new(),
+            services: Vec::new(),
+            adv_count: 1,
+            first_seen: UNIX_EPOCH,
+            last_seen: UNIX_EPOCH,
+        }
+    }
+
+    fn names(devices: &[DeviceInfo]) -> Vec<&str> {
+        devices.iter().map(|device| device.name.as_str()).collect()
+    }
+
+    #[test]
+    fn search_matches_address_name_and_nickname() {
+        let device = device("Thermo", Some(-60));
+        let search = |text: &str| Filters {
+            search: text.to_string(),
+            ..Filters::default()
+        };
+        assert!(search("aa:bb:cc").accepts(&device, None));
+        assert!(search("therm").accepts(&device, None));
+        assert!(search("kitchen").accepts(&device, Some("Kitchen")));
+        assert!(!search("11:22").accepts(&device, None));
+    }
+
+    #[test]
+    fn filters_combine() {
+        let filters = Filters {
+            hide_unknown: true,
+            min_rssi: Some(-70),
+            ..Filters::default()
+        };
+        assert!(filters.accepts(&device("Thermo", Some(-60)), None));
+        assert!(filters.accepts(&device("Thermo", None), None));
+        assert!(!filters.accepts(&device("Thermo", Some(-80)), None));
+        assert!(!filters.accepts(&device("Unknown", Some(-60)), None));
+        let connected_only = Filters {
+            connected_only: true,
+            ..Filters::default()
+        };
+        assert!(!connected_only.accepts(&device("Thermo", Some(-60)), None));
+    }
+
+    #[test]
+    fn sort_by_name_and_rssi() {
+        let mut devices = vec![
+            device("b", Some(-70)),
+            device("a", None),
+            device("c", Some(-50)),
+        ];
+        SortMode::Name.sort(&mut devices, false);
+        assert_eq!(names(&devices), ["a", "b", "c"]);
+        // Unknown RSSI stays last in either direction.
+        SortMode::Rssi.sort(&mut devices, true);
+        assert_eq!(names(&devices), ["c", "b", "a"]);
+        SortMode::Rssi.sort(&mut devices, false);
+        assert_eq!(names(&devices), ["b", "c", "a"]);
+    }
+
+    #[test]
+    fn victron_keys_must_be_16_hex_bytes() {
+        let key = parse_key("00112233445566778899aabbccddeeff").unwrap();
+        assert_eq!(key[0], 0x00);
+        assert_eq!(key[15], 0xff);
+        assert!(parse_key("00112233").is_err());
+        assert!(parse_key("zz112233445566778899aabbccddeeff").is_err());
+    }
+
+    #[test]
+    fn sort_modes_cycle() {
+        assert_eq!(SortMode::Name.next(), SortMode::Rssi);
+        assert_eq!(SortMode::LastSeen.next(), SortMode::Name);
+        assert!(!SortMode::Name.default_desc());
+        assert!(SortMode::Rssi.default_desc());
+    }
+
+    #[test]
+    fn rssi_history_samples_only_new_advertisements() {
+        let mut history = RssiHistory::default();
+        let seen = UNIX_EPOCH + Duration::from_secs(100);
+        history.record(-60, seen);
+        history.record(-60, seen);
+        history.record(-55, seen + Duration::from_secs(1));
+        history.record(-70, seen);
+        assert_eq!(history.samples, [-60, -55]);
+
+        for second in 2..=RSSI_HISTORY_LEN as u64 {
+            history.record(-50, seen + Duration::from_secs(second));
+        }
+        assert_eq!(history.samples.len(), RSSI_HISTORY_LEN);
+        assert_eq!(history.samples.front(), Some(&-55));
+    }
+}
@@ -0,0 +1,197 @@
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::io;
+use std::net::SocketAddr;
+use std::sync::mpsc as std_mpsc;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::runtime::Handle;
+
+use crate::{DeviceInfo, ScanMessage, ScanSink, SharedDecoders, detail_number, label_slug};
+
+#[derive(Default)]
+struct Snapshot {
+    devices: Vec<DeviceInfo>,
+    // Numeric decoded readings per device id, as (metric label, value).
+    readings: Vec<(String, Vec<(String, f64)>)>,
+}
+
+pub struct MetricsServer {
+    snapshot: Arc<RwLock<Snapshot>>,
+    decoders: SharedDecoders,
+    errors: std_mpsc::Receiver<String>,
+}
+
+impl MetricsServer {
+    // Binds up front so a taken or invalid address fails at startup instead of silently.
+    pub fn spawn(
+        runtime: &Handle,
+        address: SocketAddr,
+        decoders: SharedDecoders,
+    ) -> io::Result<Self> {
+        let listener = std::net::TcpListener::bind(address)?;
+        listener.set_nonblocking(true)?;
+        let listener = {
+            let _guard = runtime.enter();
+            TcpListener::from_std(listener)?
+        };
+        let snapshot = Arc::new(RwLock::new(Snapshot::default()));
+        let shared = Arc::clone(&snapshot);
+        let (status, errors) = std_mpsc::channel();
+        runtime.spawn(async move {
+            loop {
+                match listener.accept().await {
+                    Ok((stream, _)) => {
+                        let shared = Arc::clone(&shared);
+                        tokio::spawn(async move {
+                            let _ = serve(stream, &shared).await;
+                        });
+                    }
+                    // Usually transient (e.g. out of file descriptors): back off and keep serving.
+                    Err(err) => {
+                        let _ = status.send(format!("Metrics accept failed: {err}"));
+                        tokio::time::sleep(Duration::from_millis(100)).await;
+                    }
+                }
+            }
+        });
+        Ok(Self {
+            snapshot,
+            decoders,
+            errors,
+        })
+    }
+}
+
+impl ScanSink for MetricsServer {
+    fn handle(&mut self, msg: &ScanMessage) {
+        let ScanMessage::Devices(devices) = msg else {
+            return;
+        };
+        let readings = devices
+            .iter()
+            .map(|device| {
+                let values = self
+                    .decoders
+                    .iter()
+                    .flat_map(|decoder| decoder.details(device))
+                    .filter_map(|item| Some((label_slug(&item.label), detail_number(&item.value)?)))
+                    .collect();
+                (device.id.clone(), unique_readings(values))
+            })
+            .collect();
+        if let Ok(mut snapshot) = self.snapshot.write() {
+            snapshot.devices = devices.clone();
+            snapshot.readings = readings;
+        }
+    }
+
+    fn flush(&mut self) {}
+
+    fn take_status(&mut self) -> Option<String> {
+        self.errors.try_recv().ok()
+    }
+}
+
+// Two details can slug to the same label (e.g. "Temperature" from two decoders); Prometheus
+// rejects duplicate series, so later ones get a numeric suffix.
+fn unique_readings(values: Vec<(String, f64)>) -> Vec<(String, f64)> {
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    values
+        .into_iter()
+        .map(|(label, value)| {
+            let count = seen.entry(label.clone()).or_default();
+            *count += 1;
+            match *count {
+                1 => (label, value),
+                n => (format!("{label}_{n}"), value),
+            }
+        })
+        .collect()
+}
+
+async fn serve(mut stream: TcpStream, snapshot: &RwLock<Snapshot>) -> std::io::Result<()> {
+    let mut request = [0u8; 1024];
+    let len = stream.read(&mut request).await?;
+    let request = String::from_utf8_lossy(&request[..len]);
+    let path = request.split_whitespace().nth(1).unwrap_or("/");
+
+    let (status, body) = if path == "/metrics" {
+        ("200 OK", render(snapshot))
+    } else {
+        ("404 Not Found", "not found\n".to_string())
+    };
+    let response = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    stream.write_all(response.as_bytes()).await
+}
+
+fn render(snapshot: &RwLock<Snapshot>) -> String {
+    let Ok(snapshot) = snapshot.read() else {
+        return String::new();
+    };
+    let mut out = String::new();
+
+    let _ = writeln!(out, "# HELP bleah_device_rssi Last seen RSSI in dBm.");
+    let _ = writeln!(out, "# TYPE bleah_device_rssi gauge");
+    for device in &snapshot.devices {
+        if let Some(rssi) = device.rssi {
+            let _ = writeln!(out, "bleah_device_rssi{{{}}} {rssi}", labels(device));
+        }
+    }
+
+    let _ = writeln!(out, "# HELP bleah_sensor_value Numeric decoded reading.");
+    let _ = writeln!(out, "# TYPE bleah_sensor_value gauge");
+    for (id, values) in &snapshot.readings {
+        let Some(device) = snapshot.devices.iter().find(|device| device.id == *id) else {
+            continue;
+        };
+        for (reading, value) in values {
+            let _ = writeln!(
+                out,
+                "bleah_sensor_value{{{},reading=\"{reading}\"}} {value}",
+                labels(device)
+            );
+        }
+    }
+    out
+}
+
+fn labels(device: &DeviceInfo) -> String {
+    format!(
+        "address=\"{}\",name=\"{}\"",
+        escape_label(device.address()),
+        escape_label(&device.name)
+    )
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn readings_get_unique_labels() {
+        let values = vec![
+            ("temperature".to_string(), 21.5),
+            ("humidity".to_string(), 40.0),
+            ("temperature".to_string(), 22.0),
+        ];
+        let labels = unique_readings(values)
+            .into_iter()
+            .map(|(label, _)| label)
+            .collect::<Vec<_>>();
+        assert_eq!(labels, ["temperature", "humidity", "temperature_2"]);
+    }
+}
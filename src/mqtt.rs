@@ -0,0 +1,104 @@
+use std::time::Duration;
+
+use rumqttc::{AsyncClient, MqttOptions, QoS};
+use tokio::runtime::Handle;
+use tokio::sync::mpsc;
+
+use crate::{ScanMessage, ScanSink, SharedDecoders, label_slug};
+
+const QUEUE_SIZE: usize = 256;
+
+#[derive(Clone, Debug)]
+pub struct MqttConfig {
+    pub host: String,
+    pub port: u16,
+    pub base_topic: String,
+}
+
+impl Default for MqttConfig {
+    fn default() -> Self {
+        Self {
+            host: "localhost".to_string(),
+            port: 1883,
+            base_topic: "bleah".to_string(),
+        }
+    }
+}
+
+pub struct MqttSink {
+    tx: mpsc::Sender<(String, String)>,
+    base_topic: String,
+    decoders: SharedDecoders,
+}
+
+impl MqttSink {
+    pub fn spawn(runtime: &Handle, config: MqttConfig, decoders: SharedDecoders) -> Self {
+        let mut options = MqttOptions::new("bleah", config.host, config.port);
+        options.set_keep_alive(Duration::from_secs(30));
+        let (client, mut eventloop) = AsyncClient::new(options, QUEUE_SIZE);
+        let (tx, mut rx) = mpsc::channel::<(String, String)>(QUEUE_SIZE);
+
+        runtime.spawn(async move {
+            loop {
+                if eventloop.poll().await.is_err() {
+                    tokio::time::sleep(Duration::from_secs(5)).await;
+                }
+            }
+        });
+        runtime.spawn(async move {
+            while let Some((topic, payload)) = rx.recv().await {
+                let _ = client.publish(topic, QoS::AtMostOnce, false, payload).await;
+            }
+        });
+
+        Self {
+            tx,
+            base_topic: config.base_topic,
+            decoders,
+        }
+    }
+}
+
+impl ScanSink for MqttSink {
+    fn handle(&mut self, msg: &ScanMessage) {
+        let ScanMessage::Devices(devices) = msg else {
+            return;
+        };
+        for device in devices {
+            let items = self
+                .decoders
+                .iter()
+                .flat_map(|decoder| decoder.details(device));
+            for item in items {
+                let topic = format!(
+                    "{}/{}/{}",
+                    self.base_topic,
+                    topic_segment(device.address()),
+                    label_slug(&item.label)
+                );
+                // Drop readings rather than block when the broker can't keep up.
+                let _ = self.tx.try_send((topic, item.value));
+            }
+        }
+    }
+
+    fn flush(&mut self) {}
+}
+
+// Platform ids can contain '/' (BlueZ object paths), and '+' and '#' are MQTT wildcards.
+fn topic_segment(value: &str) -> String {
+    value.replace(['/', '+', '#'], "_")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn topic_segment_strips_separators_and_wildcards() {
+        let mac = "AA:BB:CC:DD:EE:FF";
+        assert_eq!(topic_segment(mac), mac);
+        assert_eq!(topic_segment("hci0/dev_AA_BB"), "hci0_dev_AA_BB");
+        assert_eq!(topic_segment("a+b#c"), "a_b_c");
+    }
+}
@@ -0,0 +1,211 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::{DeviceInfo, ScanMessage, ScanSink};
+
+// LINKTYPE_BLUETOOTH_LE_LL: a bare link-layer packet starting at the access address.
+const LINKTYPE_BLUETOOTH_LE_LL: u32 = 251;
+const ADVERTISING_ACCESS_ADDRESS: u32 = 0x8e89_bed6;
+const ADV_IND: u8 = 0x00;
+const ADV_NONCONN_IND: u8 = 0x02;
+// Legacy advertising PDUs carry at most 31 bytes of AdvData after the 6-byte address.
+const MAX_ADV_DATA: usize = 31;
+
+pub struct PcapWriter {
+    out: BufWriter<File>,
+    // Only advertisements newer than the last one written per device become packets.
+    written: HashMap<String, SystemTime>,
+}
+
+impl PcapWriter {
+    pub fn create(path: &Path) -> io::Result<Self> {
+        let mut out = BufWriter::new(File::create(path)?);
+        out.write_all(&0xa1b2_c3d4u32.to_le_bytes())?;
+        out.write_all(&2u16.to_le_bytes())?;
+        out.write_all(&4u16.to_le_bytes())?;
+        out.write_all(&0i32.to_le_bytes())?;
+        out.write_all(&0u32.to_le_bytes())?;
+        out.write_all(&65535u32.to_le_bytes())?;
+        out.write_all(&LINKTYPE_BLUETOOTH_LE_LL.to_le_bytes())?;
+        Ok(Self {
+            out,
+            written: HashMap::new(),
+        })
+    }
+
+    // The scan reports devices per poll rather than individual advertisements, so this is at
+    // most one packet per device per poll, and only for devices whose raw PDU is known.
+    fn write_device(&mut self, device: &DeviceInfo) -> io::Result<()> {
+        if self.written.get(&device.id) == Some(&device.last_seen) {
+            return Ok(());
+        }
+        let Some(packet) = link_layer_packet(device) else {
+            return Ok(());
+        };
+        self.written.insert(device.id.clone(), device.last_seen);
+
+        let timestamp = device
+            .last_seen
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        let seconds = timestamp.as_secs() as u32;
+        let micros = timestamp.subsec_micros();
+        let len = packet.len() as u32;
+        for field in [seconds, micros, len, len] {
+            self.out.write_all(&field.to_le_bytes())?;
+        }
+        self.out.write_all(&packet)
+    }
+}
+
+impl ScanSink for PcapWriter {
+    fn handle(&mut self, msg: &ScanMessage) {
+        if let ScanMessage::Devices(devices) = msg {
+            for device in devices {
+                let _ = self.write_device(device);
+            }
+        }
+    }
+
+    fn flush(&mut self) {
+        let _ = self.out.flush();
+    }
+}
+
+// Only real advertising data becomes a packet: rebuilding it from parsed properties would
+// look like a capture without being one.
+fn link_layer_packet(device: &DeviceInfo) -> Option<Vec<u8>> {
+    let adv_data = device.raw_adv.as_deref()?;
+    if adv_data.len() > MAX_ADV_DATA {
+        return None;
+    }
+    let random = matches!(
+        device.address_type,
+        Some(btleplug::api::AddressType::Random)
+    );
+
+    let mut pdu = Vec::with_capacity(8 + adv_data.len());
+    let pdu_type = match device.connectable {
+        Some(false) => ADV_NONCONN_IND,
+        _ => ADV_IND,
+    };
+    pdu.push(pdu_type | if random { 0x40 } else { 0x00 });
+    pdu.push((6 + adv_data.len()) as u8);
+    pdu.extend(address_bytes(device.address()));
+    pdu.extend(adv_data);
+
+    let mut packet = ADVERTISING_ACCESS_ADDRESS.to_le_bytes().to_vec();
+    packet.extend(&pdu);
+    packet.extend(&crc24(&pdu).to_le_bytes()[..3]);
+    Some(packet)
+}
+
+// Addresses go on the air least significant byte first; anything that isn't a MAC
+// address (e.g. a CoreBluetooth UUID standing in for one) becomes all zeros.
+fn address_bytes(address: &str) -> [u8; 6] {
+    let octets = address
+        .rsplit(':')
+        .map(|octet| u8::from_str_radix(octet, 16))
+        .collect::<Result<Vec<_>, _>>();
+    match octets {
+        Ok(octets) if octets.len() == 6 => [
+            octets[0], octets[1], octets[2], octets[3], octets[4], octets[5],
+        ],
+        _ => [0; 6],
+    }
+}
+
+// BLE link-layer CRC: polynomial 0x00065b, advertising init value 0x555555, bits in
+// transmission (LSB-first) order.
+fn crc24(data: &[u8]) -> u32 {
+    let mut crc: u32 = 0x55_5555;
+    for byte in data {
+        for bit in 0..8 {
+            let input = (u32::from(*byte) >> bit) & 1;
+            let feedback = ((crc >> 23) & 1) ^ input;
+            crc = (crc << 1) & 0xff_ffff;
+            if feedback == 1 {
+                crc ^= 0x00_065b;
+            }
+        }
+    }
+    // Transmitted MSB of the register first, so reverse into on-air byte order.
+    crc.reverse_bits() >> 8
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::parse_hex_bytes;
+
+    fn device(address: &str) -> DeviceInfo {
+        DeviceInfo {
+            id: address.to_string(),
+            address: address.to_string(),
+            name: "Unknown".to_string(),
+            rssi: Some(-60),
+            rssi_smoothed: Some(-60),
+            connected: false,
+            tx_power_level: None,
+            address_type: None,
+            appearance: None,
+            connectable: None,
+            raw_adv: None,
+            manufacturer_data: BTreeMap::new(),
+            service_data: BTreeMap::new(),
+            services: Vec::new(),
+            adv_count: 1,
+            first_seen: UNIX_EPOCH,
+            last_seen: UNIX_EPOCH,
+        }
+    }
+
+    fn hex(text: &str) -> Vec<u8> {
+        parse_hex_bytes(text).unwrap()
+    }
+
+    #[test]
+    fn crc24_matches_reference() {
+        assert_eq!(crc24(&hex("00 06 01 02 03 04 05 06")), 0xf2f542);
+        let pdu = hex("40 09 ff ee dd cc bb aa 02 01 06");
+        assert_eq!(crc24(&pdu), 0x19675e);
+    }
+
+    #[test]
+    fn packet_layout() {
+        let mut device = device("AA:BB:CC:DD:EE:FF");
+        device.address_type = Some(btleplug::api::AddressType::Random);
+        device.raw_adv = Some(hex("02 01 06"));
+        let packet = link_layer_packet(&device).unwrap();
+        assert_eq!(
+            packet,
+            hex("d6 be 89 8e 40 09 ff ee dd cc bb aa 02 01 06 5e 67 19")
+        );
+    }
+
+    #[test]
+    fn uses_bluetooth_address_not_platform_id() {
+        let mut device = device("hci0/dev_11_22_33_44_55_66");
+        device.address = "AA:BB:CC:DD:EE:FF".to_string();
+        device.raw_adv = Some(hex("02 01 06"));
+        let packet = link_layer_packet(&device).unwrap();
+        assert_eq!(&packet[6..12], hex("ff ee dd cc bb aa").as_slice());
+
+        device.address = "5C3B2A1F-0000-4000-8000-00805F9B34FB".to_string();
+        let packet = link_layer_packet(&device).unwrap();
+        assert_eq!(&packet[6..12], [0; 6].as_slice());
+    }
+
+    #[test]
+    fn skips_devices_without_raw_advertisements() {
+        let mut device = device("AA:BB:CC:DD:EE:FF");
+        assert!(link_layer_packet(&device).is_none());
+        device.raw_adv = Some(vec![0; 32]);
+        assert!(link_layer_packet(&device).is_none());
+    }
+}
@@ -0,0 +1,123 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::mpsc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use tokio::sync::{mpsc as tokio_mpsc, watch};
+
+use crate::{DeviceInfo, ScanCommand, ScanMessage};
+
+// Used when a recording has no timing of its own (a single --json snapshot).
+const DEFAULT_STEP: Duration = Duration::from_secs(2);
+// Recordings can have long gaps (a paused capture); don't make the demo wait them out.
+const MAX_STEP: Duration = Duration::from_secs(30);
+
+pub struct Snapshot {
+    pub time: SystemTime,
+    pub devices: Vec<DeviceInfo>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ReplayConfig {
+    // Fixed delay between snapshots; None follows the recorded timestamps.
+    pub interval: Option<Duration>,
+    pub looping: bool,
+}
+
+// A --json export is a single snapshot; --ndjson output is one record per device per
+// poll, grouped back into snapshots by timestamp.
+pub fn load(path: &Path) -> io::Result<Vec<Snapshot>> {
+    let text = fs::read_to_string(path)?;
+    if text.trim_start().starts_with('[') {
+        let devices: Vec<DeviceInfo> = serde_json::from_str(&text)?;
+        let time = devices
+            .iter()
+            .map(|device| device.last_seen)
+            .max()
+            .unwrap_or(UNIX_EPOCH);
+        return Ok(vec![Snapshot { time, devices }]);
+    }
+
+    let mut snapshots: Vec<Snapshot> = Vec::new();
+    for line in text.lines().filter(|line| !line.trim().is_empty()) {
+        let record: serde_json::Value = serde_json::from_str(line)?;
+        let timestamp = record
+            .get("timestamp")
+            .and_then(serde_json::Value::as_f64)
+            .unwrap_or_default();
+        let time = UNIX_EPOCH + Duration::from_secs_f64(timestamp.max(0.0));
+        let device: DeviceInfo = serde_json::from_value(record)?;
+        match snapshots.last_mut() {
+            Some(last) if last.time == time => last.devices.push(device),
+            _ => snapshots.push(Snapshot {
+                time,
+                devices: vec![device],
+            }),
+        }
+    }
+    Ok(snapshots)
+}
+
+// Stands in for scan_loop: same channels, but the devices come from a recording.
+pub async fn replay_loop(
+    snapshots: Vec<Snapshot>,
+    config: ReplayConfig,
+    tx: mpsc::Sender<ScanMessage>,
+    mut commands: tokio_mpsc::UnboundedReceiver<ScanCommand>,
+    mut shutdown: watch::Receiver<bool>,
+) {
+    if snapshots.is_empty() {
+        let status = "Replay file has no devices".to_string();
+        let _ = tx.send(ScanMessage::Status(status));
+        return;
+    }
+    let _ = tx.send(ScanMessage::Status(format!(
+        "Replaying {} snapshots",
+        snapshots.len()
+    )));
+
+    loop {
+        for (index, snapshot) in snapshots.iter().enumerate() {
+            // Shift the recorded times so ages and rates read as if the scan were live.
+            let offset = SystemTime::now()
+                .duration_since(snapshot.time)
+                .unwrap_or_default();
+            let devices = snapshot
+                .devices
+                .iter()
+                .cloned()
+                .map(|mut device| {
+                    device.first_seen += offset;
+                    device.last_seen += offset;
+                    device
+                })
+                .collect();
+            if tx.send(ScanMessage::Devices(devices)).is_err() {
+                return;
+            }
+
+            let recorded = snapshots
+                .get(index + 1)
+                .and_then(|next| next.time.duration_since(snapshot.time).ok())
+                .unwrap_or(DEFAULT_STEP);
+            let delay = config.interval.unwrap_or(recorded.min(MAX_STEP));
+            let sleep = tokio::time::sleep(delay);
+            tokio::pin!(sleep);
+            loop {
+                tokio::select! {
+                    _ = &mut sleep => break,
+                    _ = shutdown.changed() => return,
+                    Some(_) = commands.recv() => {
+                        let _ = tx.send(ScanMessage::Status(
+                            "Connecting isn't possible while replaying".to_string(),
+                        ));
+                    }
+                }
+            }
+        }
+        if !config.looping {
+            break;
+        }
+    }
+}
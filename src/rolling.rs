@@ -0,0 +1,268 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc as std_mpsc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use tokio::runtime::Handle;
+use tokio::sync::mpsc;
+
+use crate::{
+    CSV_HEADER, DeviceInfo, DeviceRecord, ScanMessage, ScanSink, SharedDecoders, csv_record,
+};
+
+const QUEUE_SIZE: usize = 64;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LogFormat {
+    // One DeviceRecord per line, the same records --ndjson prints (and --replay reads).
+    #[default]
+    Json,
+    Csv,
+}
+
+impl LogFormat {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "json" | "ndjson" => Some(LogFormat::Json),
+            "csv" => Some(LogFormat::Csv),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct RollingLogConfig {
+    pub path: PathBuf,
+    pub interval: Duration,
+    pub format: LogFormat,
+    // Start a new file once the current one would grow past this many bytes...
+    pub max_bytes: Option<u64>,
+    // ...or has been open for this long.
+    pub max_age: Option<Duration>,
+}
+
+// Appends a snapshot of the device list every `interval`; the writes happen off the UI thread.
+pub struct RollingLog {
+    tx: mpsc::Sender<String>,
+    interval: Duration,
+    format: LogFormat,
+    decoders: SharedDecoders,
+    last: Option<Instant>,
+    errors: std_mpsc::Receiver<String>,
+}
+
+impl RollingLog {
+    pub fn spawn(runtime: &Handle, config: RollingLogConfig, decoders: SharedDecoders) -> Self {
+        let (tx, mut rx) = mpsc::channel::<String>(QUEUE_SIZE);
+        // Errors come back through take_status, since stderr would draw over the TUI; each
+        // distinct error is reported once.
+        let (status, errors) = std_mpsc::channel();
+        let interval = config.interval;
+        let format = config.format;
+        runtime.spawn_blocking(move || {
+            let mut file = LogFile::new(config);
+            let mut last_error = None;
+            while let Some(batch) = rx.blocking_recv() {
+                match file.append(&batch) {
+                    Ok(()) => last_error = None,
+                    Err(err) => {
+                        let message = format!("Log {}: {err}", file.config.path.display());
+                        if last_error.as_ref() != Some(&message) {
+                            let _ = status.send(message.clone());
+                            last_error = Some(message);
+                        }
+                    }
+                }
+            }
+        });
+        Self {
+            tx,
+            interval,
+            format,
+            decoders,
+            last: None,
+            errors,
+        }
+    }
+
+    fn batch(&self, devices: &[DeviceInfo]) -> String {
+        let now = SystemTime::now();
+        let mut batch = String::new();
+        for device in devices {
+            match self.format {
+                LogFormat::Json => {
+                    let record = DeviceRecord::new(device, &self.decoders, now);
+                    let Ok(line) = serde_json::to_string(&record) else {
+                        continue;
+                    };
+                    batch.push_str(&line);
+                }
+                LogFormat::Csv => {
+                    let timestamp = now.duration_since(UNIX_EPOCH).unwrap_or_default();
+                    batch.push_str(&format!("{:.3},", timestamp.as_secs_f64()));
+                    batch.push_str(&csv_record(device, &self.decoders));
+                }
+            }
+            batch.push('\n');
+        }
+        batch
+    }
+}
+
+impl ScanSink for RollingLog {
+    fn handle(&mut self, msg: &ScanMessage) {
+        let ScanMessage::Devices(devices) = msg else {
+            return;
+        };
+        if self.last.is_some_and(|last| last.elapsed() < self.interval) {
+            return;
+        }
+        self.last = Some(Instant::now());
+        let batch = self.batch(devices);
+        if !batch.is_empty() {
+            // Drop snapshots rather than block when the disk can't keep up.
+            let _ = self.tx.try_send(batch);
+        }
+    }
+
+    fn flush(&mut self) {}
+
+    fn take_status(&mut self) -> Option<String> {
+        self.errors.try_recv().ok()
+    }
+}
+
+struct LogFile {
+    config: RollingLogConfig,
+    file: Option<File>,
+    opened: Instant,
+    written: u64,
+}
+
+impl LogFile {
+    fn new(config: RollingLogConfig) -> Self {
+        Self {
+            config,
+            file: None,
+            opened: Instant::now(),
+            written: 0,
+        }
+    }
+
+    fn append(&mut self, batch: &str) -> io::Result<()> {
+        let age = self.opened.elapsed();
+        if self.file.is_some() && should_rotate(&self.config, self.written, age, batch.len()) {
+            self.file = None;
+            let rotated = rotated_path(&self.config.path, SystemTime::now());
+            fs::rename(&self.config.path, rotated)?;
+        }
+        // On error the file is dropped and reopened with the next batch.
+        let mut file = match self.file.take() {
+            Some(file) => file,
+            None => self.open()?,
+        };
+        if self.written == 0 && self.config.format == LogFormat::Csv {
+            writeln!(file, "timestamp,{CSV_HEADER}")?;
+        }
+        file.write_all(batch.as_bytes())?;
+        file.flush()?;
+        self.written = file.metadata()?.len();
+        self.file = Some(file);
+        Ok(())
+    }
+
+    fn open(&mut self) -> io::Result<File> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.config.path)?;
+        self.written = file.metadata()?.len();
+        self.opened = Instant::now();
+        Ok(file)
+    }
+}
+
+// A file that's still empty is never rotated for size, so one oversized batch can't loop.
+fn should_rotate(config: &RollingLogConfig, written: u64, age: Duration, incoming: usize) -> bool {
+    let too_big = config
+        .max_bytes
+        .is_some_and(|max| written > 0 && written + incoming as u64 > max);
+    let too_old = config.max_age.is_some_and(|max| age >= max);
+    too_big || too_old
+}
+
+// bleah.ndjson -> bleah-1760000000123.ndjson (milliseconds), with a counter on the rare
+// clash so an earlier rotation is never overwritten.
+fn rotated_path(path: &Path, now: SystemTime) -> PathBuf {
+    let stamp = now
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let ext = path
+        .extension()
+        .map(|ext| format!(".{}", ext.to_string_lossy()))
+        .unwrap_or_default();
+    let mut candidate = path.with_file_name(format!("{stem}-{stamp}{ext}"));
+    let mut counter = 1;
+    while candidate.exists() {
+        candidate = path.with_file_name(format!("{stem}-{stamp}-{counter}{ext}"));
+        counter += 1;
+    }
+    candidate
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log_config(max_bytes: Option<u64>, max_age: Option<Duration>) -> RollingLogConfig {
+        RollingLogConfig {
+            path: "bleah.ndjson".into(),
+            interval: Duration::from_secs(10),
+            format: LogFormat::Json,
+            max_bytes,
+            max_age,
+        }
+    }
+
+    #[test]
+    fn rotates_by_size() {
+        let config = log_config(Some(100), None);
+        assert!(!should_rotate(&config, 60, Duration::ZERO, 40));
+        assert!(should_rotate(&config, 60, Duration::ZERO, 41));
+        // An empty file takes the batch however big it is.
+        assert!(!should_rotate(&config, 0, Duration::ZERO, 500));
+    }
+
+    #[test]
+    fn rotates_by_age() {
+        let hour = Duration::from_secs(3600);
+        let config = log_config(None, Some(hour));
+        assert!(!should_rotate(&config, 10, hour / 2, 10));
+        assert!(should_rotate(&config, 10, hour, 10));
+        let unlimited = log_config(None, None);
+        assert!(!should_rotate(&unlimited, 1 << 30, hour * 24, 10));
+    }
+
+    #[test]
+    fn rotated_names() {
+        let now = UNIX_EPOCH + Duration::from_millis(1_760_000_000_123);
+        let dir = std::env::temp_dir().join(format!("bleah-rotate-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("scan.ndjson");
+        let first = rotated_path(&path, now);
+        assert_eq!(first, dir.join("scan-1760000000123.ndjson"));
+        std::fs::write(&first, b"").unwrap();
+        assert_eq!(
+            rotated_path(&path, now),
+            dir.join("scan-1760000000123-1.ndjson")
+        );
+        assert_eq!(
+            rotated_path(&dir.join("scan"), now),
+            dir.join("scan-1760000000123")
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,143 @@
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use crate::{
+    DeviceInfo, DeviceRecord, ScanMessage, ScanSink, SharedDecoders, write_csv, write_json,
+};
+
+// Keeps the latest device list and writes it out once, on flush.
+// Write errors are kept for take_status rather than printed, since stderr would draw over
+// the TUI.
+pub struct JsonSnapshot {
+    path: PathBuf,
+    latest: Option<Vec<DeviceInfo>>,
+    error: Option<String>,
+}
+
+impl JsonSnapshot {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            latest: None,
+            error: None,
+        }
+    }
+}
+
+impl ScanSink for JsonSnapshot {
+    fn handle(&mut self, msg: &ScanMessage) {
+        if let ScanMessage::Devices(devices) = msg {
+            self.latest = Some(devices.clone());
+        }
+    }
+
+    fn flush(&mut self) {
+        let devices = self.latest.as_deref().unwrap_or_default();
+        if let Err(err) = write_json(devices, &self.path) {
+            self.error = Some(format!("write {}: {err}", self.path.display()));
+        }
+    }
+
+    fn take_status(&mut self) -> Option<String> {
+        self.error.take()
+    }
+}
+
+// Like JsonSnapshot, as CSV with each device's decoded summary.
+pub struct CsvSnapshot {
+    path: PathBuf,
+    decoders: SharedDecoders,
+    latest: Option<Vec<DeviceInfo>>,
+    error: Option<String>,
+}
+
+impl CsvSnapshot {
+    pub fn new(path: PathBuf, decoders: SharedDecoders) -> Self {
+        Self {
+            path,
+            decoders,
+            latest: None,
+            error: None,
+        }
+    }
+}
+
+impl ScanSink for CsvSnapshot {
+    fn handle(&mut self, msg: &ScanMessage) {
+        if let ScanMessage::Devices(devices) = msg {
+            self.latest = Some(devices.clone());
+        }
+    }
+
+    fn flush(&mut self) {
+        let devices = self.latest.as_deref().unwrap_or_default();
+        if let Err(err) = write_csv(devices, &self.decoders, &self.path) {
+            self.error = Some(format!("write {}: {err}", self.path.display()));
+        }
+    }
+
+    fn take_status(&mut self) -> Option<String> {
+        self.error.take()
+    }
+}
+
+pub struct NdjsonWriter {
+    out: io::Stdout,
+    decoders: SharedDecoders,
+    closed: bool,
+    error: Option<String>,
+}
+
+impl NdjsonWriter {
+    pub fn new(decoders: SharedDecoders) -> Self {
+        Self {
+            out: io::stdout(),
+            decoders,
+            closed: false,
+            error: None,
+        }
+    }
+
+    fn write(&mut self, devices: &[DeviceInfo]) -> io::Result<()> {
+        let now = SystemTime::now();
+        let mut out = self.out.lock();
+        for device in devices {
+            let record = DeviceRecord::new(device, &self.decoders, now);
+            serde_json::to_writer(&mut out, &record)?;
+            writeln!(out)?;
+        }
+        out.flush()
+    }
+}
+
+impl ScanSink for NdjsonWriter {
+    fn handle(&mut self, msg: &ScanMessage) {
+        let ScanMessage::Devices(devices) = msg else {
+            return;
+        };
+        if self.closed {
+            return;
+        }
+        match self.write(devices) {
+            Ok(()) => {}
+            // The reader went away (e.g. piped into `head`), so there's nobody left to serve.
+            Err(err) if err.kind() == io::ErrorKind::BrokenPipe => self.closed = true,
+            Err(err) => self.error = Some(format!("write record: {err}")),
+        }
+    }
+
+    fn flush(&mut self) {
+        if !self.closed {
+            let _ = self.out.flush();
+        }
+    }
+
+    fn finished(&self) -> bool {
+        self.closed
+    }
+
+    fn take_status(&mut self) -> Option<String> {
+        self.error.take()
+    }
+}
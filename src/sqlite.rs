@@ -0,0 +1,140 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::mpsc as std_mpsc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rusqlite::{Connection, params};
+use tokio::runtime::Handle;
+use tokio::sync::mpsc;
+
+use crate::{DeviceInfo, ScanMessage, ScanSink, hex_bytes};
+
+const QUEUE_SIZE: usize = 64;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS devices (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    first_seen_ms INTEGER NOT NULL,
+    last_seen_ms INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS observations (
+    device_id TEXT NOT NULL REFERENCES devices(id),
+    observed_ms INTEGER NOT NULL,
+    rssi INTEGER,
+    manufacturer_data TEXT NOT NULL,
+    service_data TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS observations_device ON observations(device_id, observed_ms);
+";
+
+pub struct SqliteSink {
+    tx: mpsc::Sender<Vec<DeviceInfo>>,
+    errors: std_mpsc::Receiver<String>,
+    // Set while polls are being dropped, so a backlog is reported once rather than per poll.
+    dropping: bool,
+    dropped: Option<String>,
+}
+
+impl SqliteSink {
+    pub fn spawn(runtime: &Handle, path: &Path) -> rusqlite::Result<Self> {
+        let mut conn = Connection::open(path)?;
+        conn.execute_batch(SCHEMA)?;
+        let (tx, mut rx) = mpsc::channel::<Vec<DeviceInfo>>(QUEUE_SIZE);
+        // Failures come back through take_status; each distinct error is reported once.
+        let (status, errors) = std_mpsc::channel();
+        runtime.spawn_blocking(move || {
+            let mut last_error = None;
+            while let Some(devices) = rx.blocking_recv() {
+                match write_poll(&mut conn, &devices) {
+                    Ok(()) => last_error = None,
+                    Err(err) => {
+                        let message = format!("SQLite write failed: {err}");
+                        if last_error.as_ref() != Some(&message) {
+                            let _ = status.send(message.clone());
+                            last_error = Some(message);
+                        }
+                    }
+                }
+            }
+        });
+        Ok(Self {
+            tx,
+            errors,
+            dropping: false,
+            dropped: None,
+        })
+    }
+}
+
+impl ScanSink for SqliteSink {
+    fn handle(&mut self, msg: &ScanMessage) {
+        if let ScanMessage::Devices(devices) = msg {
+            // Drop polls rather than block when the disk can't keep up.
+            match self.tx.try_send(devices.clone()) {
+                Ok(()) => self.dropping = false,
+                Err(_) if !self.dropping => {
+                    self.dropping = true;
+                    self.dropped = Some("SQLite can't keep up, dropping polls".to_string());
+                }
+                Err(_) => {}
+            }
+        }
+    }
+
+    fn flush(&mut self) {}
+
+    fn take_status(&mut self) -> Option<String> {
+        self.dropped.take().or_else(|| self.errors.try_recv().ok())
+    }
+}
+
+// One transaction per poll keeps a busy scan to a single fsync.
+fn write_poll(conn: &mut Connection, devices: &[DeviceInfo]) -> rusqlite::Result<()> {
+    let observed_ms = millis(SystemTime::now());
+    let tx = conn.transaction()?;
+    {
+        let mut upsert = tx.prepare_cached(
+            "INSERT INTO devices (id, name, first_seen_ms, last_seen_ms) VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(id) DO UPDATE SET name = excluded.name, last_seen_ms = excluded.last_seen_ms",
+        )?;
+        let mut observe = tx.prepare_cached(
+            "INSERT INTO observations (device_id, observed_ms, rssi, manufacturer_data, service_data)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+        )?;
+        for device in devices {
+            upsert.execute(params![
+                device.id,
+                device.name,
+                millis(device.first_seen),
+                millis(device.last_seen),
+            ])?;
+            let manufacturer_data = device
+                .manufacturer_data
+                .iter()
+                .map(|(id, data)| (*id, hex_bytes(data)))
+                .collect::<BTreeMap<_, _>>();
+            let service_data = device
+                .service_data
+                .iter()
+                .map(|(uuid, data)| (uuid.clone(), hex_bytes(data)))
+                .collect::<BTreeMap<_, _>>();
+            observe.execute(params![
+                device.id,
+                observed_ms,
+                device.rssi,
+                serde_json::to_string(&manufacturer_data).unwrap_or_default(),
+                serde_json::to_string(&service_data).unwrap_or_default(),
+            ])?;
+        }
+    }
+    tx.commit()
+}
+
+fn millis(time: SystemTime) -> i64 {
+    let millis = time
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+    i64::try_from(millis).unwrap_or(i64::MAX)
+}
@@ -0,0 +1,73 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+const CONFIG_FILE: &str = "config.toml";
+const NICKNAMES_FILE: &str = "nicknames.json";
+const PINNED_FILE: &str = "pinned.json";
+// Superseded by [victron_keys] in config.toml, but still read so existing keys keep working.
+const VICTRON_KEYS_FILE: &str = "victron_keys.json";
+
+// Unlike the JSON state files, a config file the user wrote but got wrong is an error.
+pub fn load_config<T: DeserializeOwned + Default>() -> io::Result<T> {
+    let Some(path) = config_path(CONFIG_FILE) else {
+        return Ok(T::default());
+    };
+    let text = match fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(T::default()),
+        Err(err) => return Err(err),
+    };
+    toml::from_str(&text).map_err(|err| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{}: {err}", path.display()),
+        )
+    })
+}
+
+pub fn load_nicknames() -> BTreeMap<String, String> {
+    load(NICKNAMES_FILE)
+}
+
+pub fn save_nicknames(nicknames: &BTreeMap<String, String>) -> io::Result<()> {
+    save(NICKNAMES_FILE, nicknames)
+}
+
+pub fn load_pinned() -> BTreeSet<String> {
+    load(PINNED_FILE)
+}
+
+pub fn save_pinned(pinned: &BTreeSet<String>) -> io::Result<()> {
+    save(PINNED_FILE, pinned)
+}
+
+// Maps device address to the hex encryption key shown in the VictronConnect app.
+pub fn load_victron_keys() -> BTreeMap<String, String> {
+    load(VICTRON_KEYS_FILE)
+}
+
+fn config_path(file: &str) -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("bleah").join(file))
+}
+
+// A missing or unreadable file just means nothing has been saved yet.
+fn load<T: DeserializeOwned + Default>(file: &str) -> T {
+    config_path(file)
+        .and_then(|path| fs::read(path).ok())
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .unwrap_or_default()
+}
+
+fn save<T: Serialize>(file: &str, value: &T) -> io::Result<()> {
+    let path = config_path(file)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no config directory"))?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, serde_json::to_vec_pretty(value)?)
+}
@@ -0,0 +1,155 @@
+use std::str::FromStr;
+
+use anyhow::{Result, anyhow, bail};
+use ratatui::style::{Color, Modifier, Style};
+use serde::Deserialize;
+
+#[derive(Clone, Debug)]
+pub struct Theme {
+    pub header: Style,
+    pub highlight: Style,
+    pub status: Color,
+    pub accent: Color,
+    pub rssi_strong: Color,
+    pub rssi_medium: Color,
+    pub rssi_weak: Color,
+    pub rssi_unknown: Color,
+    // The glyph of a possible tracker, and rows flashing for an alert.
+    pub tracker: Color,
+    pub alert: Color,
+    // Readings above rssi_strong_above are strong, below rssi_weak_below weak (dBm).
+    pub rssi_strong_above: i16,
+    pub rssi_weak_below: i16,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            header: Style::default().add_modifier(Modifier::BOLD),
+            highlight: Style::default().add_modifier(Modifier::REVERSED),
+            status: Color::Yellow,
+            accent: Color::Cyan,
+            rssi_strong: Color::Green,
+            rssi_medium: Color::Yellow,
+            rssi_weak: Color::Red,
+            rssi_unknown: Color::Gray,
+            tracker: Color::Red,
+            alert: Color::Yellow,
+            rssi_strong_above: -60,
+            rssi_weak_below: -80,
+        }
+    }
+}
+
+impl Theme {
+    pub fn light() -> Self {
+        Self {
+            header: Style::default().add_modifier(Modifier::BOLD),
+            highlight: Style::default().fg(Color::Black).bg(Color::LightBlue),
+            status: Color::Blue,
+            accent: Color::Blue,
+            rssi_strong: Color::Green,
+            rssi_medium: Color::Magenta,
+            rssi_weak: Color::Red,
+            rssi_unknown: Color::DarkGray,
+            alert: Color::Magenta,
+            ..Self::default()
+        }
+    }
+
+    pub fn preset(name: &str) -> Option<Self> {
+        match name {
+            "default" | "dark" => Some(Self::default()),
+            "light" => Some(Self::light()),
+            _ => None,
+        }
+    }
+
+    pub fn rssi_color(&self, rssi: Option<i16>) -> Color {
+        match rssi {
+            Some(value) if value > self.rssi_strong_above => self.rssi_strong,
+            Some(value) if value >= self.rssi_weak_below => self.rssi_medium,
+            Some(_) => self.rssi_weak,
+            None => self.rssi_unknown,
+        }
+    }
+}
+
+// The `[theme]` table: a preset plus optional per-color overrides, using ratatui color
+// names ("yellow", "#ffaa00", "208").
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ThemeConfig {
+    pub preset: Option<String>,
+    pub status: Option<String>,
+    pub accent: Option<String>,
+    pub highlight_fg: Option<String>,
+    pub highlight_bg: Option<String>,
+    pub rssi_strong: Option<String>,
+    pub rssi_medium: Option<String>,
+    pub rssi_weak: Option<String>,
+    pub rssi_unknown: Option<String>,
+    pub tracker: Option<String>,
+    pub alert: Option<String>,
+    pub rssi_strong_above: Option<i16>,
+    pub rssi_weak_below: Option<i16>,
+}
+
+impl ThemeConfig {
+    pub fn build(&self) -> Result<Theme> {
+        let mut theme = match &self.preset {
+            Some(name) => Theme::preset(name).ok_or_else(|| anyhow!("unknown theme {name:?}"))?,
+            None => Theme::default(),
+        };
+        if let Some(color) = parse_color(&self.status)? {
+            theme.status = color;
+        }
+        if let Some(color) = parse_color(&self.accent)? {
+            theme.accent = color;
+        }
+        if let Some(color) = parse_color(&self.highlight_fg)? {
+            theme.highlight = theme.highlight.fg(color);
+        }
+        if let Some(color) = parse_color(&self.highlight_bg)? {
+            theme.highlight = theme
+                .highlight
+                .bg(color)
+                .remove_modifier(Modifier::REVERSED);
+        }
+        if let Some(color) = parse_color(&self.rssi_strong)? {
+            theme.rssi_strong = color;
+        }
+        if let Some(color) = parse_color(&self.rssi_medium)? {
+            theme.rssi_medium = color;
+        }
+        if let Some(color) = parse_color(&self.rssi_weak)? {
+            theme.rssi_weak = color;
+        }
+        if let Some(color) = parse_color(&self.rssi_unknown)? {
+            theme.rssi_unknown = color;
+        }
+        if let Some(color) = parse_color(&self.tracker)? {
+            theme.tracker = color;
+        }
+        if let Some(color) = parse_color(&self.alert)? {
+            theme.alert = color;
+        }
+        theme.rssi_strong_above = self.rssi_strong_above.unwrap_or(theme.rssi_strong_above);
+        theme.rssi_weak_below = self.rssi_weak_below.unwrap_or(theme.rssi_weak_below);
+        if theme.rssi_weak_below > theme.rssi_strong_above {
+            bail!(
+                "rssi_weak_below ({}) must not be above rssi_strong_above ({})",
+                theme.rssi_weak_below,
+                theme.rssi_strong_above
+            );
+        }
+        Ok(theme)
+    }
+}
+
+fn parse_color(value: &Option<String>) -> Result<Option<Color>> {
+    value
+        .as_deref()
+        .map(|name| Color::from_str(name).map_err(|_| anyhow!("unknown color {name:?}")))
+        .transpose()
+}